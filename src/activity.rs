@@ -0,0 +1,134 @@
+use crate::locale::Locale;
+use chrono::{Datelike, NaiveDate};
+use colored::Colorize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+// Kind of contribution behind a day's count
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ActivityKind {
+    Commits,
+    PullRequests,
+    Reviews,
+    Issues,
+    Other,
+}
+
+impl ActivityKind {
+    // Kind of a public event from its type, e.g. "PushEvent"
+    pub fn from_event(kind: &str) -> Self {
+        match kind {
+            "PushEvent" => ActivityKind::Commits,
+            "PullRequestEvent" => ActivityKind::PullRequests,
+            "PullRequestReviewEvent" | "PullRequestReviewCommentEvent" => ActivityKind::Reviews,
+            "IssuesEvent" | "IssueCommentEvent" => ActivityKind::Issues,
+            _ => ActivityKind::Other,
+        }
+    }
+
+    // Label shown next to the kind's total
+    pub fn label(self) -> &'static str {
+        match self {
+            ActivityKind::Commits => "Commits",
+            ActivityKind::PullRequests => "Pull requests",
+            ActivityKind::Reviews => "Reviews",
+            ActivityKind::Issues => "Issues",
+            ActivityKind::Other => "Other",
+        }
+    }
+}
+
+// Contributions of one kind to one repository on a day
+#[derive(Clone, Debug)]
+pub struct Activity {
+    pub date: NaiveDate,
+    pub kind: ActivityKind,
+    pub repository: String,
+    // Whether the repository is private, so only tokens with access see it
+    pub private: bool,
+    pub count: u32,
+    // Commit message, pull request or issue title, when known
+    pub title: Option<String>,
+    pub url: Option<String>,
+}
+
+// A day's activity totaled by kind and by repository
+#[derive(Debug, Default)]
+pub struct DaySummary {
+    pub kinds: BTreeMap<ActivityKind, u32>,
+    pub repositories: BTreeMap<String, u32>,
+}
+
+impl DaySummary {
+    // Repositories with their totals, busiest first
+    pub fn busiest_repositories(&self) -> Vec<(&str, u32)> {
+        let mut repositories: Vec<(&str, u32)> = self.repositories.iter()
+            .map(|(name, count)| (name.as_str(), *count))
+            .collect();
+        repositories.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        repositories
+    }
+}
+
+// Group activity into a summary for each day
+pub fn by_day(activities: &[Activity]) -> HashMap<NaiveDate, DaySummary> {
+    let mut days: HashMap<NaiveDate, DaySummary> = HashMap::new();
+    for activity in activities {
+        let day = days.entry(activity.date).or_default();
+        *day.kinds.entry(activity.kind).or_insert(0) += activity.count;
+        *day.repositories.entry(activity.repository.clone()).or_insert(0) += activity.count;
+    }
+    days
+}
+
+// Format a day's activity grouped by kind and repository, with titles and links
+pub fn day_report(username: &str, date: NaiveDate, activities: &[Activity], locale: &Locale) -> String {
+    let mut out = String::new();
+    let total: u32 = activities.iter().map(|activity| activity.count).sum();
+    let _ = writeln!(
+        out,
+        "\n{} on {} {}: {} contributions",
+        username.bright_white().bold(),
+        locale.weekday(date.weekday()),
+        date.format("%Y-%m-%d"),
+        total.to_string().green()
+    );
+
+    let mut kinds: BTreeMap<ActivityKind, BTreeMap<&str, Vec<&Activity>>> = BTreeMap::new();
+    for activity in activities {
+        kinds.entry(activity.kind)
+            .or_default()
+            .entry(&activity.repository)
+            .or_default()
+            .push(activity);
+    }
+
+    for (kind, repositories) in &kinds {
+        let kind_total: u32 = repositories.values().flatten().map(|activity| activity.count).sum();
+        let _ = writeln!(out, "\n{} ({})", kind.label().bold(), kind_total);
+
+        for (repository, entries) in repositories {
+            let _ = writeln!(out, "  {}", repository.bright_white());
+            for entry in entries {
+                match &entry.title {
+                    Some(title) => {
+                        let _ = writeln!(out, "    {}", title);
+                    }
+                    // Only a count is known, e.g. for commits outside the default branch
+                    None => {
+                        let _ = writeln!(out, "    {} {}", entry.count, kind.label().to_lowercase());
+                    }
+                }
+                if let Some(url) = &entry.url {
+                    let _ = writeln!(out, "      {}", url.dimmed());
+                }
+            }
+        }
+    }
+
+    if kinds.is_empty() {
+        let _ = writeln!(out, "\nNo contributions found.");
+    }
+    out.push('\n');
+    out
+}
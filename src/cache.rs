@@ -0,0 +1,107 @@
+use crate::error::GhHeatError;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+// Contributions saved between runs in $XDG_CACHE_HOME/gh-heat/<user>.json
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Cache {
+    // When contributions were last fetched
+    pub updated: Option<DateTime<Utc>>,
+    pub contributions: BTreeMap<NaiveDate, u32>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Cache {
+    // Load a user's cached contributions, starting empty when there are none
+    pub fn load(username: &str) -> Result<Self> {
+        let path = Self::file(username);
+        let mut cache = match &path {
+            Some(path) if path.exists() => {
+                let contents = std::fs::read_to_string(path)
+                    .map_err(|e| GhHeatError::Cache(format!("{}: {}", path.display(), e)))?;
+                serde_json::from_str(&contents)
+                    .map_err(|e| GhHeatError::Cache(format!("{}: {}", path.display(), e)))?
+            }
+            _ => Self::default(),
+        };
+        cache.path = path;
+
+        Ok(cache)
+    }
+
+    // Directory holding the cache files
+    pub fn dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+
+        Some(base.join("gh-heat"))
+    }
+
+    // Cache file of a user
+    pub fn file(username: &str) -> Option<PathBuf> {
+        Self::dir().map(|dir| dir.join(format!("{}.json", username.to_lowercase())))
+    }
+
+    // Delete a user's cache file, or every cache file, returning how many were removed
+    pub fn clear(username: Option<&str>) -> Result<usize> {
+        let files = match username {
+            Some(username) => Self::file(username).into_iter().filter(|path| path.exists()).collect(),
+            None => match Self::dir().filter(|dir| dir.exists()) {
+                Some(dir) => std::fs::read_dir(&dir)
+                    .map_err(|e| GhHeatError::Cache(format!("{}: {}", dir.display(), e)))?
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+                    .collect(),
+                None => Vec::new(),
+            },
+        };
+        for path in &files {
+            std::fs::remove_file(path)
+                .map_err(|e| GhHeatError::Cache(format!("{}: {}", path.display(), e)))?;
+        }
+
+        Ok(files.len())
+    }
+
+    // Store counts fetched at a moment, returning the days whose count went up
+    pub fn merge(&mut self, contributions: &HashMap<NaiveDate, u32>, fetched: DateTime<Utc>) -> Vec<NaiveDate> {
+        let mut increased = Vec::new();
+        for (date, count) in contributions {
+            let previous = self.contributions.insert(*date, *count);
+            if *count > previous.unwrap_or(0) {
+                increased.push(*date);
+            }
+        }
+        self.updated = Some(fetched);
+
+        increased.sort();
+        increased
+    }
+
+    // Write the cache file, creating its directory if needed
+    pub fn save(&self) -> Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| GhHeatError::Cache(format!("{}: {}", dir.display(), e)))?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)
+            .map_err(|e| GhHeatError::Cache(format!("{}: {}", path.display(), e)))?;
+
+        Ok(())
+    }
+
+    // Cached counts by date
+    pub fn contributions(&self) -> HashMap<NaiveDate, u32> {
+        self.contributions.iter().map(|(date, count)| (*date, *count)).collect()
+    }
+}
@@ -0,0 +1,41 @@
+use crate::zone::Zone;
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+
+// Where the current time comes from: the system clock, or a fixed moment for reproducible output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Clock {
+    zone: Zone,
+    fixed: Option<DateTime<Utc>>,
+}
+
+impl Clock {
+    // The system clock, with days in a time zone
+    pub fn system(zone: Zone) -> Self {
+        Self { zone, fixed: None }
+    }
+
+    // A clock stopped at one moment, with days in a time zone
+    pub fn fixed(now: DateTime<Utc>, zone: Zone) -> Self {
+        Self { zone, fixed: Some(now) }
+    }
+
+    // Zone that days start and end in
+    pub fn zone(&self) -> Zone {
+        self.zone
+    }
+
+    // The current moment
+    pub fn now_utc(&self) -> DateTime<Utc> {
+        self.fixed.unwrap_or_else(Utc::now)
+    }
+
+    // The current time in the clock's zone
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.zone.convert(&self.now_utc())
+    }
+
+    // The current date in the clock's zone
+    pub fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+}
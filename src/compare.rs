@@ -0,0 +1,150 @@
+use crate::heatmap::{Heatmap, RenderMode, RenderOptions, Span, WeekStart};
+use crate::stats::Report;
+use chrono::{Duration, NaiveDate};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// Width of the row labels in the stats table
+const TABLE_LABEL_WIDTH: usize = 30;
+
+// Minimum width of a value column in the stats table
+const TABLE_COLUMN_WIDTH: usize = 10;
+
+// One side of a comparison: a user's contributions over a period
+pub struct Side {
+    pub label: String,
+    pub contributions: HashMap<NaiveDate, u32>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+// Two heatmaps drawn over the same weeks, with their difference and statistics
+pub struct Comparison {
+    labels: (String, String),
+    left: Heatmap,
+    right: Heatmap,
+    // The right side moved onto the left side's dates, for the difference
+    aligned: Heatmap,
+    reports: (Report, Report),
+}
+
+impl Comparison {
+    // Line up two periods week by week, shifting the second by whole weeks so weekdays match
+    pub fn new(left: Side, right: Side, week_start: WeekStart) -> Self {
+        let offset = (right.start_date - left.start_date).num_days();
+        let shift = Duration::weeks((offset + 3).div_euclid(7));
+
+        // Shared calendar covering both periods, in the left side's dates
+        let start_date = left.start_date.min(right.start_date - shift);
+        let end_date = left.end_date.max(right.end_date - shift);
+
+        let aligned_contributions = right.contributions.iter()
+            .map(|(date, count)| (*date - shift, *count))
+            .collect();
+        let aligned = Heatmap::with_date_range(aligned_contributions, week_start, start_date, end_date);
+
+        let reports = (
+            Report::new(&Heatmap::with_date_range(left.contributions.clone(), week_start, left.start_date, left.end_date)),
+            Report::new(&Heatmap::with_date_range(right.contributions.clone(), week_start, right.start_date, right.end_date)),
+        );
+
+        Self {
+            labels: (left.label, right.label),
+            left: Heatmap::with_date_range(left.contributions, week_start, start_date, end_date),
+            right: Heatmap::with_date_range(right.contributions, week_start, start_date + shift, end_date + shift),
+            aligned,
+            reports,
+        }
+    }
+
+    // Pick a render mode that fits the width, as for a single heatmap
+    pub fn fitting_mode(&self, mode: RenderMode, max_width: usize) -> RenderMode {
+        self.left.fitting_mode(mode, max_width)
+    }
+
+    // Render both heatmaps, their difference and a table of statistics
+    pub fn render_to_string(&self, options: &RenderOptions) -> String {
+        let mut out = String::new();
+        let (left_label, right_label) = (&self.labels.0, &self.labels.1);
+
+        let _ = writeln!(out, "\n{}", left_label.bright_white().bold());
+        out.push_str(&self.left.render_to_string(options));
+        let _ = writeln!(out, "\n{}", right_label.bright_white().bold());
+        out.push_str(&self.right.render_to_string(options));
+
+        let _ = writeln!(out, "\n{}", format!("Difference ({} - {})", right_label, left_label).bright_white().bold());
+        out.push_str(&self.left.render_difference(&self.aligned, options, (left_label, right_label)));
+
+        out.push_str(&self.stats_table());
+        out
+    }
+
+    // Table of statistics for both sides and the change from left to right
+    fn stats_table(&self) -> String {
+        let (left, right) = &self.reports;
+        let span_days = |span: Option<Span>| span.map_or(0.0, |span| span.days as f64);
+
+        // (label, left value, right value, decimals)
+        let rows = [
+            ("Total Contributions", left.total_contributions as f64, right.total_contributions as f64, 0),
+            ("Active Days", left.active_days as f64, right.active_days as f64, 0),
+            ("Max Contributions in a Day", left.max_contributions_in_day as f64, right.max_contributions_in_day as f64, 0),
+            ("Average on Active Days", left.average_on_active_days, right.average_on_active_days, 2),
+            ("Median on Active Days", left.median_on_active_days, right.median_on_active_days, 1),
+            ("90th Percentile on Active Days", left.p90_on_active_days as f64, right.p90_on_active_days as f64, 0),
+            ("Current Streak (days)", span_days(left.streaks.current), span_days(right.streaks.current), 0),
+            ("Longest Streak (days)", span_days(left.streaks.longest), span_days(right.streaks.longest), 0),
+            ("Longest Gap (days)", span_days(left.streaks.longest_gap), span_days(right.streaks.longest_gap), 0),
+        ];
+
+        let column_width = |label: &str| label.chars().count().max(TABLE_COLUMN_WIDTH);
+        let left_width = column_width(&self.labels.0);
+        let right_width = column_width(&self.labels.1);
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "\n{}  {}  {}  {}",
+            " ".repeat(TABLE_LABEL_WIDTH),
+            format!("{:>width$}", self.labels.0, width = left_width).bright_white().bold(),
+            format!("{:>width$}", self.labels.1, width = right_width).bright_white().bold(),
+            "Change".bright_white().bold()
+        );
+
+        for (label, left_value, right_value, decimals) in rows {
+            let change = right_value - left_value;
+            let percent = if left_value > 0.0 {
+                format!(" ({:+.1}%)", change / left_value * 100.0)
+            } else {
+                String::new()
+            };
+            // Color by the change as printed, so a rounded zero stays plain
+            let rounded = (change * 10f64.powi(decimals as i32)).round();
+            let text = format!("{:+.*}{}", decimals, change, percent);
+            let change = if rounded > 0.0 {
+                text.green()
+            } else if rounded < 0.0 {
+                text.red()
+            } else {
+                text.normal()
+            };
+
+            let _ = writeln!(
+                out,
+                "{:<label$}  {:>left$.decimals$}  {:>right$.decimals$}  {}",
+                label,
+                left_value,
+                right_value,
+                change,
+                label = TABLE_LABEL_WIDTH,
+                left = left_width,
+                right = right_width,
+                decimals = decimals
+            );
+        }
+
+        out.push('\n');
+        out
+    }
+}
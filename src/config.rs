@@ -0,0 +1,122 @@
+use crate::error::GhHeatError;
+use crate::github::Source;
+use crate::heatmap::WeekStart;
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// User configuration loaded from $XDG_CONFIG_HOME/gh-heat/config.toml
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    // Custom color themes keyed by name
+    #[serde(default)]
+    pub themes: HashMap<String, ThemeConfig>,
+    // Defaults for command-line options
+    #[serde(default)]
+    pub defaults: Settings,
+    // Named settings selected with --profile, layered over the defaults
+    #[serde(default)]
+    pub profile: HashMap<String, Settings>,
+}
+
+// Options that can be set in the config file; flags given on the command line take precedence
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    pub theme: Option<String>,
+    // Number of days, like --days
+    pub days: Option<u32>,
+    pub week_start: Option<WeekStart>,
+    pub source: Option<Source>,
+    // A GitHub Enterprise Server host like github.example.com
+    pub host: Option<String>,
+    // A leading ~/ stands for the home directory
+    pub token_file: Option<PathBuf>,
+}
+
+impl Settings {
+    // These settings, with unset ones taken from another set
+    fn or(self, fallback: Settings) -> Settings {
+        Settings {
+            theme: self.theme.or(fallback.theme),
+            days: self.days.or(fallback.days),
+            week_start: self.week_start.or(fallback.week_start),
+            source: self.source.or(fallback.source),
+            host: self.host.or(fallback.host),
+            token_file: self.token_file.or(fallback.token_file),
+        }
+    }
+}
+
+// A user-defined theme with hex colors
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeConfig {
+    // Background for days without contributions
+    pub empty: Option<String>,
+    // Colors from the lowest to the highest intensity
+    pub levels: Vec<String>,
+}
+
+impl Config {
+    // Load the config file, falling back to defaults when it does not exist
+    pub fn load() -> Result<Self> {
+        let path = match Self::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Self::default()),
+        };
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| GhHeatError::Config(format!("{}: {}", path.display(), e)))?;
+        let config = Self::parse(&contents)
+            .map_err(|e| GhHeatError::Config(format!("{}: {}", path.display(), e)))?;
+
+        Ok(config)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    // The defaults with a profile's settings on top
+    pub fn settings(&self, profile: Option<&str>) -> Result<Settings> {
+        let defaults = self.defaults.clone();
+        let settings = match profile {
+            Some(name) => {
+                let profile = self.profile.get(name).ok_or_else(|| {
+                    let mut names: Vec<&str> = self.profile.keys().map(String::as_str).collect();
+                    names.sort_unstable();
+                    let known = if names.is_empty() { "none defined".to_string() } else { names.join(", ") };
+                    GhHeatError::Config(format!("unknown profile '{}' (profiles: {})", name, known))
+                })?;
+                profile.clone().or(defaults)
+            }
+            None => defaults,
+        };
+
+        Ok(Settings {
+            token_file: settings.token_file.map(expand_home),
+            ..settings
+        })
+    }
+
+    // Location of the config file
+    pub fn path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+        Some(base.join("gh-heat").join("config.toml"))
+    }
+}
+
+// Replace a leading ~/ with the home directory
+fn expand_home(path: PathBuf) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path,
+    }
+}
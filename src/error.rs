@@ -1,3 +1,4 @@
+use chrono::{DateTime, FixedOffset};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -5,18 +6,129 @@ pub enum GhHeatError {
     #[error("Network error: {0}")]
     Network(#[from] reqwest::Error),
     
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+    
     #[error("GitHub API error: {0}")]
     Api(String),
     
-    #[error("Failed to parse data: {0}")]
-    Parse(String),
+    #[error("GitHub user '{0}' not found")]
+    UserNotFound(String),
+    
+    // A missing, bad or expired token, or a suspended account
+    #[error("Not authorized: {0}")]
+    Unauthorized(String),
+    
+    #[error("GitHub server error {status} while fetching {what}")]
+    ServerError { status: u16, what: String },
+    
+    #[error("Failed to parse {what} near: {snippet}")]
+    ParseFailure {
+        what: String,
+        // The source text where parsing failed, shortened to one line
+        snippet: String,
+    },
     
     #[error("Invalid date format: {0}")]
     InvalidDate(String),
     
-    #[error("Rate limit exceeded. Please try again later.")]
-    RateLimit,
+    #[error("Git error: {0}")]
+    Git(String),
+    
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+    
+    #[error("Cache error: {0}")]
+    Cache(String),
+    
+    #[error("{}", rate_limit_message(*.secondary, .retry_at))]
+    RateLimit {
+        // Secondary limits guard against bursts and clear within minutes
+        secondary: bool,
+        // When requests are accepted again, in the clock's time zone
+        retry_at: Option<DateTime<FixedOffset>>,
+    },
+    
+    #[error("Token lacks the required permission: {0}")]
+    InsufficientScope(String),
     
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
+
+// Process exit codes, so scripts can tell failures apart; clap exits with 2 on bad usage
+pub mod exit {
+    pub const FAILURE: u8 = 1;
+    pub const USER_NOT_FOUND: u8 = 3;
+    pub const UNAUTHORIZED: u8 = 4;
+    pub const INSUFFICIENT_SCOPE: u8 = 5;
+    pub const RATE_LIMIT: u8 = 6;
+    pub const SERVER_ERROR: u8 = 7;
+    pub const TIMEOUT: u8 = 8;
+    pub const NETWORK: u8 = 9;
+    pub const API: u8 = 10;
+    pub const PARSE: u8 = 11;
+    pub const CONFIG: u8 = 12;
+    pub const CACHE: u8 = 13;
+    pub const GIT: u8 = 14;
+}
+
+// Longest snippet of source text shown in a parse error
+const SNIPPET_LENGTH: usize = 80;
+
+impl GhHeatError {
+    // A parse failure showing the source text around a byte offset
+    pub fn parse_failure(what: &str, source: &str, offset: usize) -> Self {
+        let mut start = offset.min(source.len()).saturating_sub(SNIPPET_LENGTH / 2);
+        while !source.is_char_boundary(start) {
+            start -= 1;
+        }
+        let snippet: String = source[start..]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(SNIPPET_LENGTH)
+            .collect();
+
+        GhHeatError::ParseFailure {
+            what: what.to_string(),
+            snippet: if snippet.is_empty() { "(empty)".to_string() } else { snippet },
+        }
+    }
+
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            GhHeatError::Network(error) if error.is_timeout() => exit::TIMEOUT,
+            GhHeatError::Network(_) => exit::NETWORK,
+            GhHeatError::Timeout(_) => exit::TIMEOUT,
+            GhHeatError::Api(_) => exit::API,
+            GhHeatError::UserNotFound(_) => exit::USER_NOT_FOUND,
+            GhHeatError::Unauthorized(_) => exit::UNAUTHORIZED,
+            GhHeatError::ServerError { .. } => exit::SERVER_ERROR,
+            GhHeatError::ParseFailure { .. } | GhHeatError::InvalidDate(_) => exit::PARSE,
+            GhHeatError::Git(_) => exit::GIT,
+            GhHeatError::Config(_) => exit::CONFIG,
+            GhHeatError::Cache(_) => exit::CACHE,
+            GhHeatError::RateLimit { .. } => exit::RATE_LIMIT,
+            GhHeatError::InsufficientScope(_) => exit::INSUFFICIENT_SCOPE,
+            GhHeatError::Other(error) => exit_code(error),
+        }
+    }
+}
+
+// Exit code for an error from anywhere, using the first GhHeatError in its chain
+pub fn exit_code(error: &anyhow::Error) -> u8 {
+    error.chain()
+        .find_map(|cause| cause.downcast_ref::<GhHeatError>())
+        .map_or(exit::FAILURE, GhHeatError::exit_code)
+}
+
+// Describe a rate limit and when it resets, if known
+fn rate_limit_message(secondary: bool, retry_at: &Option<DateTime<FixedOffset>>) -> String {
+    let kind = if secondary { "Secondary rate limit" } else { "Rate limit" };
+    match retry_at {
+        Some(retry_at) => format!("{} exceeded. Try again after {}.", kind, retry_at.format("%Y-%m-%d %H:%M:%S %:z")),
+        None => format!("{} exceeded. Please try again later.", kind),
+    }
+}
@@ -0,0 +1,44 @@
+use crate::github::{PrivateTotals, Source};
+use crate::heatmap::Heatmap;
+use crate::stats::Report;
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+// JSON document describing a user's contributions
+#[derive(Serialize)]
+struct Export<'a> {
+    user: &'a str,
+    // graphql or scrape, whichever produced the counts
+    source: Source,
+    // Private contribution totals, from GraphQL only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    private: Option<PrivateTotals>,
+    stats: Report,
+    contributions: Vec<Day>,
+}
+
+#[derive(Serialize)]
+struct Day {
+    date: NaiveDate,
+    count: u32,
+}
+
+// Serialize the heatmap data and statistics as pretty-printed JSON
+pub fn to_json(username: &str, heatmap: &Heatmap, source: Source, private: Option<PrivateTotals>) -> Result<String> {
+    let mut contributions: Vec<Day> = heatmap.contributions()
+        .iter()
+        .map(|(date, count)| Day { date: *date, count: *count })
+        .collect();
+    contributions.sort_by_key(|day| day.date);
+
+    let export = Export {
+        user: username,
+        source,
+        private,
+        stats: Report::new(heatmap),
+        contributions,
+    };
+
+    Ok(serde_json::to_string_pretty(&export)?)
+}
@@ -0,0 +1,34 @@
+use crate::error::GhHeatError;
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset};
+use std::path::Path;
+use std::process::Command;
+
+// Read author timestamps of commits in a local repository
+pub fn commit_timestamps(repo: &Path, author: Option<&str>, days: u32) -> Result<Vec<DateTime<FixedOffset>>> {
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(repo)
+        .args(["log", "--all", "--format=%aI"])
+        .arg(format!("--since={} days ago", days));
+    if let Some(author) = author {
+        command.arg(format!("--author={}", author));
+    }
+
+    let output = command.output()
+        .map_err(|e| GhHeatError::Git(format!("failed to run git: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GhHeatError::Git(stderr.trim().to_string()).into());
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            DateTime::parse_from_rfc3339(line)
+                .map_err(|_| GhHeatError::InvalidDate(line.to_string()).into())
+        })
+        .collect()
+}
@@ -1,66 +1,838 @@
+mod limits;
+
+use crate::activity::{Activity, ActivityKind};
 use crate::error::GhHeatError;
 use anyhow::Result;
-use chrono::{Datelike, Duration, NaiveDate, Utc};
-use reqwest::blocking::Client;
+use crate::clock::Clock;
+use crate::token;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate};
+use limits::Limits;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
 use std::collections::HashMap;
 
+// A public event from the events API
+#[derive(Deserialize, Debug)]
+struct Event {
+    #[serde(rename = "type")]
+    kind: String,
+    created_at: String,
+    payload: Option<EventPayload>,
+    repo: Option<EventRepo>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EventPayload {
+    size: Option<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EventRepo {
+    name: String,
+}
+
+impl Event {
+    // A push counts once for each commit it contains
+    fn weight(&self) -> u32 {
+        match (self.kind.as_str(), &self.payload) {
+            ("PushEvent", Some(EventPayload { size: Some(size) })) => (*size).max(1),
+            _ => 1,
+        }
+    }
+}
+
+// A commit found in a repository's history
+struct Commit {
+    repository: String,
+    timestamp: DateTime<FixedOffset>,
+    headline: String,
+    url: String,
+}
+
+// Where contribution counts come from
+#[derive(clap::ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    /// GraphQL with a token, falling back to the public page when it fails
+    Auto,
+    /// The GraphQL API only, failing on any error (requires a token)
+    Graphql,
+    /// The public contributions page, with counts approximated from color levels
+    Scrape,
+}
+
+impl Source {
+    // One-line note on where data came from, for output
+    pub fn describe(self) -> &'static str {
+        match self {
+            Source::Auto => "automatic",
+            Source::Graphql => "GraphQL API",
+            Source::Scrape => "public contributions page (approximate counts)",
+        }
+    }
+}
+
+// Daily contribution counts and the source that produced them, Graphql or Scrape
+#[derive(Clone, Debug)]
+pub struct Contributions {
+    pub counts: HashMap<NaiveDate, u32>,
+    pub source: Source,
+    // Only GraphQL reports private contributions
+    pub private: Option<PrivateTotals>,
+    // Why auto mode fell back from GraphQL to the public page
+    pub fallback: Option<String>,
+}
+
+// Private contributions over the fetched range
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PrivateTotals {
+    // Contributions the token can't see (restrictedContributionsCount). GitHub gives only a total
+    // for the range, so these can't be placed on days
+    pub restricted: u32,
+    // hasAnyRestrictedContributions
+    pub any_restricted: bool,
+    // Contributions in private repositories the token can see, when folded in
+    pub visible: Option<u32>,
+    // What folding raised the daily counts by in total
+    pub folded: u32,
+}
+
+// URLs of the GitHub web site, REST API base and GraphQL endpoint, without a trailing slash
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Endpoints {
+    pub web: String,
+    pub api: String,
+    pub graphql: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            web: "https://github.com".to_string(),
+            api: "https://api.github.com".to_string(),
+            graphql: "https://api.github.com/graphql".to_string(),
+        }
+    }
+}
+
+impl Endpoints {
+    // github.com, or a GitHub Enterprise Server host, which serves its APIs under /api
+    pub fn for_host(host: &str) -> Self {
+        if host == token::GITHUB_HOST {
+            return Self::default();
+        }
+
+        let web = match host.contains("://") {
+            true => host.trim_end_matches('/').to_string(),
+            false => format!("https://{}", host.trim_end_matches('/')),
+        };
+        Self {
+            api: format!("{}/api/v3", web),
+            graphql: format!("{}/api/graphql", web),
+            web,
+        }
+    }
+    
+    // Host name of the web endpoint, which picks the token variables to read
+    pub fn host(&self) -> &str {
+        let host = self.web.split_once("://").map_or(self.web.as_str(), |(_, rest)| rest);
+        host.trim_end_matches('/')
+    }
+}
+
 // The client for interacting with GitHub API
 pub struct GithubClient {
     client: Client,
+    // Today's date and the zone that timestamps are bucketed into days in
+    clock: Clock,
+    endpoints: Endpoints,
+    // Set on first use unless given up front
+    token: OnceCell<Option<String>>,
+    // Finds a token the first time a request needs one, instead of the token variables for the host
+    token_lookup: Option<Box<dyn Fn() -> Option<String>>>,
+    // How many times to retry server errors and secondary rate limits
+    retries: u32,
+    source: Source,
+    // Add contributions to private repositories the token can see to the daily counts
+    fold_private: bool,
+    // Keep progress and retry notes off stderr, e.g. while a full-screen view is drawn
+    quiet: bool,
 }
 
 impl GithubClient {
-    pub fn new() -> Result<Self> {
+    pub fn new(clock: Clock) -> Result<Self> {
         let client = Client::builder()
             .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
             .build()?;
         
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            clock,
+            endpoints: Endpoints::default(),
+            token: OnceCell::new(),
+            token_lookup: None,
+            retries: 0,
+            source: Source::Auto,
+            fold_private: false,
+            quiet: false,
+        })
+    }
+    
+    // Send requests to other base URLs, e.g. a GitHub Enterprise server or a test stand-in
+    pub fn with_endpoints(self, endpoints: Endpoints) -> Self {
+        Self { endpoints, ..self }
+    }
+    
+    // Use a token other than the one in the token variables for the host, or none
+    pub fn with_token(self, token: Option<String>) -> Self {
+        Self { token: OnceCell::from(token), token_lookup: None, ..self }
     }
     
-    pub fn get_user_contributions(&self, username: &str, days: u32) -> Result<HashMap<NaiveDate, u32>> {
+    // Look for a token only when a request needs one, since lookups may start gh or git
+    pub fn with_token_lookup(self, lookup: impl Fn() -> Option<String> + 'static) -> Self {
+        Self { token: OnceCell::new(), token_lookup: Some(Box::new(lookup)), ..self }
+    }
+    
+    // Wait and retry server errors and secondary rate limits up to a number of times
+    pub fn with_retries(self, retries: u32) -> Self {
+        Self { retries, ..self }
+    }
+    
+    // Where to fetch contribution counts from
+    pub fn with_source(self, source: Source) -> Self {
+        Self { source, ..self }
+    }
+    
+    // Fold contributions to private repositories the token can see into the daily counts
+    pub fn with_private(self, fold_private: bool) -> Self {
+        Self { fold_private, ..self }
+    }
+    
+    // Don't print progress and retry notes
+    pub fn with_quiet(self, quiet: bool) -> Self {
+        Self { quiet, ..self }
+    }
+    
+    pub fn get_user_contributions(&self, username: &str, days: u32) -> Result<Contributions> {
         // Get the current date and calculate the start date
-        let end_date = Utc::now().naive_utc().date();
+        let end_date = self.clock.today();
         let start_date = end_date - Duration::days(days as i64);
         
-        // Try the authenticated GraphQL API first if token is available
-        if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-            if !token.is_empty() {
-                match self.fetch_contributions_graphql(username, start_date, end_date, &token) {
-                    Ok(contributions) => return Ok(contributions),
-                    Err(_) => {
-                        // Fall back to REST API if GraphQL fails
-                        println!("Note: GraphQL API access failed, falling back to public API");
+        self.fetch_contributions(username, start_date, end_date, None)
+    }
+    
+    // Fetch user contributions between two dates, at most a year apart
+    pub fn get_user_contributions_between(
+        &self,
+        username: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Contributions> {
+        let range = (start_date, end_date);
+        let mut contributions = self.fetch_contributions(username, start_date, end_date, Some(range))?;
+        
+        // Both sources return whole weeks or the public calendar's year, so trim to the range
+        contributions.counts.retain(|date, _| *date >= start_date && *date <= end_date);
+        Ok(contributions)
+    }
+    
+    // Fetch contributions from the selected source. In auto mode GraphQL is tried when a token
+    // is set, and a failure is reported before falling back to the public calendar
+    fn fetch_contributions(
+        &self,
+        username: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        range: Option<(NaiveDate, NaiveDate)>,
+    ) -> Result<Contributions> {
+        let graphql = |token: &str| -> Result<Contributions> {
+            let mut contributions = self.fetch_contributions_graphql(username, start_date, end_date, token)?;
+            if self.fold_private {
+                self.fold_private(username, start_date, end_date, token, &mut contributions)?;
+            }
+            Ok(contributions)
+        };
+        let scrape = || -> Result<Contributions> {
+            let counts = self.fetch_contributions_rest(username, range)?;
+            Ok(Contributions { counts, source: Source::Scrape, private: None, fallback: None })
+        };
+        
+        // Scraping needs no token, so don't look for one
+        let token = match self.source {
+            Source::Scrape => None,
+            _ => self.token(),
+        };
+        match (self.source, token) {
+            (Source::Graphql, Some(token)) => graphql(&token),
+            (Source::Graphql, None) => {
+                Err(GhHeatError::Unauthorized(token_required("--source graphql")).into())
+            }
+            (Source::Scrape, _) | (Source::Auto, None) => scrape(),
+            (Source::Auto, Some(token)) => match graphql(&token) {
+                Err(error) if can_fall_back(&error) => {
+                    self.note(format_args!("Note: GraphQL failed ({}), falling back to the public contributions page", error));
+                    Ok(Contributions { fallback: Some(error.to_string()), ..scrape()? })
+                }
+                result => result,
+            },
+        }
+    }
+    
+    // Token for authenticated requests, if set
+    fn token(&self) -> Option<String> {
+        self.token
+            .get_or_init(|| match &self.token_lookup {
+                Some(lookup) => lookup(),
+                None => token::from_env(self.endpoints.host()).map(|token| token.value),
+            })
+            .clone()
+    }
+    
+    // Whether a token is available for authenticated requests
+    pub fn has_token(&self) -> bool {
+        self.token().is_some()
+    }
+    
+    // A progress or diagnostic line on stderr, unless quiet
+    fn note(&self, message: std::fmt::Arguments) {
+        if !self.quiet {
+            eprintln!("{}", message);
+        }
+    }
+    
+    // Fetch timestamps of recent public activity from the events API (at most 300 events)
+    pub fn get_event_timestamps(&self, username: &str) -> Result<Vec<DateTime<FixedOffset>>> {
+        let mut timestamps = Vec::new();
+        for event in self.fetch_events(username)? {
+            let timestamp = DateTime::parse_from_rfc3339(&event.created_at)
+                .map_err(|_| GhHeatError::InvalidDate(event.created_at.clone()))?;
+            timestamps.extend(std::iter::repeat_n(timestamp, event.weight() as usize));
+        }
+        
+        Ok(timestamps)
+    }
+    
+    // Fetch what the user contributed to on each day between two dates, from GraphQL
+    // with a token and otherwise from recent public events
+    pub fn get_activity(&self, username: &str, start_date: NaiveDate, end_date: NaiveDate) -> Result<Vec<Activity>> {
+        let activities = match self.token() {
+            Some(token) => self.fetch_activity_graphql(username, start_date, end_date, &token)?,
+            None => {
+                let mut activities = Vec::new();
+                for event in self.fetch_events(username)? {
+                    let timestamp = DateTime::parse_from_rfc3339(&event.created_at)
+                        .map_err(|_| GhHeatError::InvalidDate(event.created_at.clone()))?;
+                    activities.push(Activity {
+                        date: self.clock.zone().date_of(&timestamp),
+                        kind: ActivityKind::from_event(&event.kind),
+                        repository: event.repo.as_ref().map_or_else(String::new, |repo| repo.name.clone()),
+                        // The events API only lists public activity
+                        private: false,
+                        count: event.weight(),
+                        title: None,
+                        url: None,
+                    });
+                }
+                activities
+            }
+        };
+        
+        Ok(activities.into_iter()
+            .filter(|activity| activity.date >= start_date && activity.date <= end_date)
+            .collect())
+    }
+    
+    // Fetch everything the user contributed on one day, listing each commit with its message
+    // where the repository's default branch has it (requires auth token)
+    pub fn get_day_activity(&self, username: &str, date: NaiveDate) -> Result<Vec<Activity>> {
+        let token = self.token()
+            .ok_or_else(|| GhHeatError::Unauthorized(token_required("a day's activity")))?;
+        let contributions = self.fetch_activity_graphql(username, date, date, &token)?;
+        let commits = self.fetch_commits(username, date, date)?;
+        
+        let mut activities: Vec<Activity> = commits.into_iter()
+            .map(|commit| Activity {
+                date: self.clock.zone().date_of(&commit.timestamp),
+                kind: ActivityKind::Commits,
+                private: contributions.iter().any(|activity| activity.private && activity.repository == commit.repository),
+                repository: commit.repository,
+                count: 1,
+                title: Some(commit.headline),
+                url: Some(commit.url),
+            })
+            .collect();
+        
+        // Commit counts stay for repositories whose commits are not on the default branch
+        for activity in contributions {
+            let listed = activity.kind == ActivityKind::Commits
+                && activities.iter().any(|commit| commit.repository == activity.repository);
+            if !listed {
+                activities.push(activity);
+            }
+        }
+        
+        Ok(activities)
+    }
+    
+    // Fetch up to 300 recent public events, newest first
+    fn fetch_events(&self, username: &str) -> Result<Vec<Event>> {
+        let mut all_events = Vec::new();
+        for page in 1..=3 {
+            let url = format!("{}/users/{}/events/public?per_page=100&page={}", self.endpoints.api, username, page);
+            let mut request = self.client.get(&url).header("Accept", "application/vnd.github+json");
+            if let Some(token) = self.token() {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+            
+            let response = self.send(request, "events", Some(username))?;
+            let events: Vec<Event> = parse_json("events", &response.text()?)?;
+            if events.is_empty() {
+                break;
+            }
+            all_events.extend(events);
+        }
+        
+        Ok(all_events)
+    }
+    
+    // Fetch commits, pull requests, reviews and issues by day and repository (requires auth token)
+    fn fetch_activity_graphql(
+        &self,
+        username: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        token: &str,
+    ) -> Result<Vec<Activity>> {
+        // Only the first 100 entries of each kind are listed
+        const ACTIVITY_QUERY: &str = r#"
+        query($username: String!, $from: DateTime!, $to: DateTime!) {
+          user(login: $username) {
+            contributionsCollection(from: $from, to: $to) {
+              commitContributionsByRepository(maxRepositories: 100) {
+                repository {
+                  nameWithOwner
+                  isPrivate
+                }
+                contributions(first: 100) {
+                  nodes {
+                    occurredAt
+                    commitCount
+                  }
+                }
+              }
+              pullRequestContributions(first: 100) {
+                nodes {
+                  occurredAt
+                  pullRequest {
+                    title
+                    url
+                    repository {
+                      nameWithOwner
+                      isPrivate
+                    }
+                  }
+                }
+              }
+              pullRequestReviewContributions(first: 100) {
+                nodes {
+                  occurredAt
+                  pullRequestReview {
+                    url
+                    pullRequest {
+                      title
+                    }
+                    repository {
+                      nameWithOwner
+                      isPrivate
+                    }
+                  }
+                }
+              }
+              issueContributions(first: 100) {
+                nodes {
+                  occurredAt
+                  issue {
+                    title
+                    url
+                    repository {
+                      nameWithOwner
+                      isPrivate
                     }
+                  }
+                }
+              }
+            }
+          }
+        }
+        "#;
+
+        #[derive(Deserialize, Debug)]
+        struct Data {
+            user: Option<User>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct User {
+            contributions_collection: ContributionsCollection,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct ContributionsCollection {
+            commit_contributions_by_repository: Vec<RepositoryCommits>,
+            pull_request_contributions: Connection<PullRequestNode>,
+            pull_request_review_contributions: Connection<ReviewNode>,
+            issue_contributions: Connection<IssueNode>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct Connection<T> {
+            nodes: Vec<T>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct RepositoryCommits {
+            repository: Repository,
+            contributions: Connection<CommitNode>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct Repository {
+            name_with_owner: String,
+            #[serde(default)]
+            is_private: bool,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct Item {
+            title: String,
+            url: String,
+            repository: Repository,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct Review {
+            url: String,
+            pull_request: Titled,
+            repository: Repository,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct Titled {
+            title: String,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct CommitNode {
+            occurred_at: String,
+            commit_count: u32,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct PullRequestNode {
+            occurred_at: String,
+            pull_request: Item,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct ReviewNode {
+            occurred_at: String,
+            pull_request_review: Review,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct IssueNode {
+            occurred_at: String,
+            issue: Item,
+        }
+        
+        let activity = |occurred_at: &str, kind: ActivityKind, repository: &Repository, count: u32| -> Result<Activity> {
+            let timestamp = DateTime::parse_from_rfc3339(occurred_at)
+                .map_err(|_| GhHeatError::InvalidDate(occurred_at.to_string()))?;
+            Ok(Activity {
+                date: self.clock.zone().date_of(&timestamp),
+                kind,
+                repository: repository.name_with_owner.clone(),
+                private: repository.is_private,
+                count,
+                title: None,
+                url: None,
+            })
+        };
+        let titled = |activity: Activity, title: &str, url: &str| Activity {
+            title: Some(title.to_string()),
+            url: Some(url.to_string()),
+            ..activity
+        };
+        
+        let mut activities = Vec::new();
+        for (from, to) in year_windows(start_date, end_date) {
+            let variables = serde_json::json!({
+                "username": username,
+                "from": self.clock.zone().start_of_day(from).to_rfc3339(),
+                "to": self.clock.zone().end_of_day(to).to_rfc3339(),
+            });
+            let data: Data = self.post_graphql(ACTIVITY_QUERY, variables, token)?;
+            let collection = data.user
+                .ok_or_else(|| GhHeatError::UserNotFound(username.to_string()))?
+                .contributions_collection;
+            
+            for commits in &collection.commit_contributions_by_repository {
+                for node in &commits.contributions.nodes {
+                    activities.push(activity(&node.occurred_at, ActivityKind::Commits, &commits.repository, node.commit_count)?);
                 }
             }
+            for node in &collection.pull_request_contributions.nodes {
+                let pull_request = &node.pull_request;
+                let entry = activity(&node.occurred_at, ActivityKind::PullRequests, &pull_request.repository, 1)?;
+                activities.push(titled(entry, &pull_request.title, &pull_request.url));
+            }
+            for node in &collection.pull_request_review_contributions.nodes {
+                let review = &node.pull_request_review;
+                let entry = activity(&node.occurred_at, ActivityKind::Reviews, &review.repository, 1)?;
+                activities.push(titled(entry, &review.pull_request.title, &review.url));
+            }
+            for node in &collection.issue_contributions.nodes {
+                let issue = &node.issue;
+                let entry = activity(&node.occurred_at, ActivityKind::Issues, &issue.repository, 1)?;
+                activities.push(titled(entry, &issue.title, &issue.url));
+            }
         }
         
-        // Fall back to public REST API
-        self.fetch_contributions_rest(username)
+        Ok(activities)
     }
     
-    // Fetch user contributions using public API
-    fn fetch_contributions_rest(&self, username: &str) -> Result<HashMap<NaiveDate, u32>> {
-        // Alternative approach - use the GitHub API directly to get the last year of events
-        let url = format!("https://github.com/users/{}/contributions", username);
+    // Fetch commit timestamps from each repository the user committed to (requires auth token)
+    pub fn get_commit_timestamps(&self, username: &str, days: u32) -> Result<Vec<DateTime<FixedOffset>>> {
+        let end_date = self.clock.today();
+        let start_date = end_date - Duration::days(days as i64);
         
-        println!("Fetching contributions from: {}", url);
+        let commits = self.fetch_commits(username, start_date, end_date)?;
+        Ok(commits.into_iter().map(|commit| commit.timestamp).collect())
+    }
+    
+    // Fetch the user's commits on the default branch of each repository they committed to
+    // between two dates (requires auth token)
+    fn fetch_commits(&self, username: &str, start_date: NaiveDate, end_date: NaiveDate) -> Result<Vec<Commit>> {
+        const REPOSITORIES_QUERY: &str = r#"
+        query($username: String!, $from: DateTime!, $to: DateTime!) {
+          user(login: $username) {
+            id
+            contributionsCollection(from: $from, to: $to) {
+              commitContributionsByRepository(maxRepositories: 100) {
+                repository {
+                  name
+                  owner {
+                    login
+                  }
+                }
+              }
+            }
+          }
+        }
+        "#;
         
-        let response = self.client
-            .get(&url)
-            .send()?;
+        const HISTORY_QUERY: &str = r#"
+        query($owner: String!, $name: String!, $author: ID!, $since: GitTimestamp!, $until: GitTimestamp!, $cursor: String) {
+          repository(owner: $owner, name: $name) {
+            defaultBranchRef {
+              target {
+                ... on Commit {
+                  history(first: 100, after: $cursor, author: {id: $author}, since: $since, until: $until) {
+                    pageInfo {
+                      hasNextPage
+                      endCursor
+                    }
+                    nodes {
+                      committedDate
+                      messageHeadline
+                      url
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }
+        "#;
         
-        if !response.status().is_success() {
-            return Err(GhHeatError::Api(format!("Failed to fetch data: {}", response.status())).into());
+        // Commits beyond this many pages per repository are not counted
+        const MAX_PAGES: usize = 10;
+
+        #[derive(Deserialize, Debug)]
+        struct RepositoriesData {
+            user: Option<User>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct User {
+            id: String,
+            #[serde(rename = "contributionsCollection")]
+            contributions_collection: ContributionsCollection,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct ContributionsCollection {
+            #[serde(rename = "commitContributionsByRepository")]
+            commit_contributions_by_repository: Vec<RepositoryContributions>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct RepositoryContributions {
+            repository: Repository,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct Repository {
+            name: String,
+            owner: Owner,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct Owner {
+            login: String,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct HistoryData {
+            repository: Option<HistoryRepository>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct HistoryRepository {
+            #[serde(rename = "defaultBranchRef")]
+            default_branch_ref: Option<BranchRef>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct BranchRef {
+            target: Target,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct Target {
+            history: Option<History>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct History {
+            #[serde(rename = "pageInfo")]
+            page_info: PageInfo,
+            nodes: Vec<CommitNode>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct PageInfo {
+            #[serde(rename = "hasNextPage")]
+            has_next_page: bool,
+            #[serde(rename = "endCursor")]
+            end_cursor: Option<String>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct CommitNode {
+            #[serde(rename = "committedDate")]
+            committed_date: String,
+            #[serde(rename = "messageHeadline")]
+            message_headline: String,
+            url: String,
+        }
+        
+        let token = self.token()
+            .ok_or_else(|| GhHeatError::Unauthorized(token_required("commit history")))?;
+        let since = self.clock.zone().start_of_day(start_date).to_rfc3339();
+        let until = self.clock.zone().end_of_day(end_date).to_rfc3339();
+        
+        // Repositories committed to in any of the year-long windows, each listed once. Commit history
+        // itself has no range limit
+        let mut author = String::new();
+        let mut repositories: Vec<Repository> = Vec::new();
+        for (from, to) in year_windows(start_date, end_date) {
+            let variables = serde_json::json!({
+                "username": username,
+                "from": self.clock.zone().start_of_day(from).to_rfc3339(),
+                "to": self.clock.zone().end_of_day(to).to_rfc3339(),
+            });
+            let data: RepositoriesData = self.post_graphql(REPOSITORIES_QUERY, variables, &token)?;
+            let user = data.user
+                .ok_or_else(|| GhHeatError::UserNotFound(username.to_string()))?;
+            author = user.id;
+            for contributions in user.contributions_collection.commit_contributions_by_repository {
+                let repository = contributions.repository;
+                let known = repositories.iter()
+                    .any(|known| known.name == repository.name && known.owner.login == repository.owner.login);
+                if !known {
+                    repositories.push(repository);
+                }
+            }
         }
         
+        let mut commits = Vec::new();
+        for repository in &repositories {
+            let mut cursor: Option<String> = None;
+            
+            for _ in 0..MAX_PAGES {
+                let variables = serde_json::json!({
+                    "owner": repository.owner.login,
+                    "name": repository.name,
+                    "author": author,
+                    "since": since,
+                    "until": until,
+                    "cursor": cursor,
+                });
+                let data: HistoryData = self.post_graphql(HISTORY_QUERY, variables, &token)?;
+                let history = match data.repository
+                    .and_then(|repository| repository.default_branch_ref)
+                    .and_then(|branch| branch.target.history)
+                {
+                    Some(history) => history,
+                    None => break,
+                };
+                
+                for node in history.nodes {
+                    let timestamp = DateTime::parse_from_rfc3339(&node.committed_date)
+                        .map_err(|_| GhHeatError::InvalidDate(node.committed_date.clone()))?;
+                    commits.push(Commit {
+                        repository: format!("{}/{}", repository.owner.login, repository.name),
+                        timestamp,
+                        headline: node.message_headline,
+                        url: node.url,
+                    });
+                }
+                
+                if !history.page_info.has_next_page {
+                    break;
+                }
+                cursor = history.page_info.end_cursor;
+            }
+        }
+        
+        Ok(commits)
+    }
+    
+    // Fetch user contributions using public API, for the last year or a given date range
+    fn fetch_contributions_rest(&self, username: &str, range: Option<(NaiveDate, NaiveDate)>) -> Result<HashMap<NaiveDate, u32>> {
+        // Alternative approach - use the GitHub API directly to get the last year of events
+        let mut url = format!("{}/users/{}/contributions", self.endpoints.web, username);
+        if let Some((start_date, end_date)) = range {
+            url.push_str(&format!("?from={}&to={}", start_date.format("%Y-%m-%d"), end_date.format("%Y-%m-%d")));
+        }
+        
+        self.note(format_args!("Fetching contributions from: {}", url));
+        
+        let response = self.send(self.client.get(&url), "data", Some(username))?;
         let html = response.text()?;
         
-        // Create a dummy set of contributions for testing
         let mut contributions = HashMap::new();
-        let today = Utc::now().naive_utc().date();
         
         // Parse the HTML with regex - looking for the data-date and data-level attributes
         let rect_regex = regex::Regex::new(r#"data-date="([0-9]{4}-[0-9]{2}-[0-9]{2})"[^>]*data-level="([0-9]+)"[^>]*>"#).unwrap();
@@ -128,21 +900,10 @@ impl GithubClient {
             }
         }
         
-        // If we still don't have contributions, let's create some random data
-        // rather than erroring out, for demo purposes
+        // A page without a calendar isn't a year of zeros; show its title to hint at what came back
         if contributions.is_empty() {
-            println!("Warning: Could not parse GitHub contribution data. Using simulated data.");
-            
-            // Generate 365 days of random contribution data
-            let mut date = today - Duration::days(365);
-            while date <= today {
-                let weekday = date.weekday().num_days_from_monday();
-                // Make weekend days have fewer contributions on average
-                let max_val = if weekday >= 5 { 5 } else { 10 };
-                let count = rand::random::<u32>() % max_val;
-                contributions.insert(date, count);
-                date = date + Duration::days(1);
-            }
+            let title = html.find("<title").unwrap_or(0);
+            return Err(GhHeatError::parse_failure("contribution calendar", &html, title).into());
         }
         
         Ok(contributions)
@@ -155,12 +916,14 @@ impl GithubClient {
         start_date: NaiveDate, 
         end_date: NaiveDate, 
         token: &str
-    ) -> Result<HashMap<NaiveDate, u32>> {
+    ) -> Result<Contributions> {
         // GraphQL query
         const CONTRIBUTION_QUERY: &str = r#"
         query($username: String!, $from: DateTime!, $to: DateTime!) {
           user(login: $username) {
             contributionsCollection(from: $from, to: $to) {
+              restrictedContributionsCount
+              hasAnyRestrictedContributions
               contributionCalendar {
                 weeks {
                   contributionDays {
@@ -183,17 +946,6 @@ impl GithubClient {
         }
         
         // Structs for GraphQL response
-        #[derive(Deserialize, Debug)]
-        struct GraphQLResponse {
-            data: Option<Data>,
-            errors: Option<Vec<GraphQLError>>,
-        }
-
-        #[derive(Deserialize, Debug)]
-        struct GraphQLError {
-            message: String,
-        }
-
         #[derive(Deserialize, Debug)]
         struct Data {
             user: Option<User>,
@@ -206,8 +958,12 @@ impl GithubClient {
         }
 
         #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
         struct ContributionsCollection {
-            #[serde(rename = "contributionCalendar")]
+            #[serde(default)]
+            restricted_contributions_count: u32,
+            #[serde(default)]
+            has_any_restricted_contributions: bool,
             contribution_calendar: ContributionCalendar,
         }
 
@@ -229,33 +985,122 @@ impl GithubClient {
             contribution_count: u32,
         }
         
-        let variables = QueryVariables {
-            username: username.to_string(),
-            from: format!("{}", start_date.format("%Y-%m-%dT00:00:00")),
-            to: format!("{}", end_date.format("%Y-%m-%dT23:59:59")),
-        };
+        let mut counts = HashMap::new();
+        let mut private = PrivateTotals::default();
+        for (from, to) in year_windows(start_date, end_date) {
+            let variables = QueryVariables {
+                username: username.to_string(),
+                from: self.clock.zone().start_of_day(from).to_rfc3339(),
+                to: self.clock.zone().end_of_day(to).to_rfc3339(),
+            };
+            
+            let data: Data = self.post_graphql(CONTRIBUTION_QUERY, serde_json::to_value(variables)?, token)?;
+            let user = data.user
+                .ok_or_else(|| GhHeatError::UserNotFound(username.to_string()))?;
+            
+            let collection = user.contributions_collection;
+            private.restricted += collection.restricted_contributions_count;
+            private.any_restricted |= collection.has_any_restricted_contributions;
+            
+            // Process the contributions data, leaving padding days to the window they belong to
+            for week in &collection.contribution_calendar.weeks {
+                for day in &week.contribution_days {
+                    let date = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
+                        .map_err(|_| GhHeatError::InvalidDate(day.date.clone()))?;
+                    if date >= from && date <= to {
+                        counts.insert(date, day.contribution_count);
+                    }
+                }
+            }
+        }
+        
+        Ok(Contributions { counts, source: Source::Graphql, private: Some(private), fallback: None })
+    }
+    
+    // Raise each day's count to the total of the user's contributions by repository that the token
+    // can see, which includes private repositories it has access to. Taking the larger of the two
+    // never counts a contribution twice when the calendar already has it
+    fn fold_private(
+        &self,
+        username: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        token: &str,
+        contributions: &mut Contributions,
+    ) -> Result<()> {
+        let activities = self.fetch_activity_graphql(username, start_date, end_date, token)?;
+        let mut visible: HashMap<NaiveDate, u32> = HashMap::new();
+        for activity in &activities {
+            *visible.entry(activity.date).or_insert(0) += activity.count;
+        }
+        
+        let mut folded = 0;
+        for (date, count) in visible {
+            let day = contributions.counts.entry(date).or_insert(0);
+            if count > *day {
+                folded += count - *day;
+                *day = count;
+            }
+        }
+        
+        if let Some(private) = &mut contributions.private {
+            private.visible = Some(activities.iter().filter(|activity| activity.private).map(|activity| activity.count).sum());
+            private.folded = folded;
+        }
+        Ok(())
+    }
+    
+    // Send a GraphQL query and return the data of a successful response
+    fn post_graphql<T: DeserializeOwned>(&self, query: &str, variables: serde_json::Value, token: &str) -> Result<T> {
+        #[derive(Deserialize, Debug)]
+        struct GraphQLResponse<T> {
+            data: Option<T>,
+            errors: Option<Vec<GraphQLError>>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct GraphQLError {
+            message: String,
+            // e.g. RATE_LIMITED or INSUFFICIENT_SCOPES
+            #[serde(rename = "type")]
+            kind: Option<String>,
+        }
         
+        // Queries name the user they're about as $username
+        let username = variables.get("username").and_then(|username| username.as_str()).map(str::to_string);
         let query_body = serde_json::json!({
-            "query": CONTRIBUTION_QUERY,
+            "query": query,
             "variables": variables,
         });
         
-        let response = self.client
-            .post("https://api.github.com/graphql")
+        let request = self.client
+            .post(&self.endpoints.graphql)
             .header("Authorization", format!("Bearer {}", token))
-            .json(&query_body)
-            .send()?;
+            .json(&query_body);
+        let response = self.send(request, "data", None)?;
         
-        if !response.status().is_success() {
-            if response.status() == reqwest::StatusCode::FORBIDDEN {
-                return Err(GhHeatError::RateLimit.into());
-            }
-            return Err(GhHeatError::Api(format!("Failed to fetch data: {}", response.status())).into());
-        }
-        
-        let graphql_response: GraphQLResponse = response.json()?;
+        // GraphQL reports rate limits and missing scopes as errors in a successful response
+        let limits = Limits::from_headers(response.headers());
+        let body = response.text()?;
+        let graphql_response: GraphQLResponse<T> = parse_json("GraphQL response", &body)?;
         
         if let Some(errors) = graphql_response.errors {
+            let kind = |kind: &str| errors.iter().any(|e| e.kind.as_deref() == Some(kind));
+            if kind("RATE_LIMITED") {
+                return Err(limits.rate_limit(self.clock).into());
+            }
+            if let (true, Some(username)) = (kind("NOT_FOUND"), username) {
+                return Err(GhHeatError::UserNotFound(username).into());
+            }
+            if kind("INSUFFICIENT_SCOPES") {
+                let message = errors.iter()
+                    .filter(|e| e.kind.as_deref() == Some("INSUFFICIENT_SCOPES"))
+                    .map(|e| e.message.clone())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(GhHeatError::InsufficientScope(message).into());
+            }
+            
             let error_msg = errors.iter()
                 .map(|e| e.message.clone())
                 .collect::<Vec<_>>()
@@ -263,22 +1108,87 @@ impl GithubClient {
             return Err(GhHeatError::Api(error_msg).into());
         }
         
-        let user = graphql_response.data
-            .ok_or_else(|| GhHeatError::Parse("No data in response".to_string()))?
-            .user
-            .ok_or_else(|| GhHeatError::Parse("User not found".to_string()))?;
-        
-        let mut contributions = HashMap::new();
-        
-        // Process the contributions data
-        for week in &user.contributions_collection.contribution_calendar.weeks {
-            for day in &week.contribution_days {
-                let date = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
-                    .map_err(|_| GhHeatError::InvalidDate(day.date.clone()))?;
-                contributions.insert(date, day.contribution_count);
+        graphql_response.data
+            .ok_or_else(|| GhHeatError::parse_failure("GraphQL response without data", &body, 0).into())
+    }
+    
+    // Send a request, waiting and retrying server errors and secondary rate limits as configured
+    fn send(&self, request: RequestBuilder, what: &str, user: Option<&str>) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let retry = request.try_clone()
+                .ok_or_else(|| GhHeatError::Api("Request cannot be retried".to_string()))?;
+            let response = retry.send().map_err(|error| match error.is_timeout() {
+                true => GhHeatError::Timeout(format!("no response while fetching {}", what)),
+                false => GhHeatError::Network(error),
+            })?;
+            if response.status().is_success() {
+                return Ok(response);
+            }
+            
+            let status = response.status();
+            let limits = Limits::from_headers(response.headers());
+            let body = response.text().unwrap_or_default();
+            let error = limits.error(status, &body, self.clock, what, user);
+            match limits.retry_delay(&error, attempt) {
+                Some(delay) if attempt < self.retries => {
+                    attempt += 1;
+                    self.note(format_args!("{} Retrying in {}s ({} of {})", error, delay.as_secs(), attempt, self.retries));
+                    std::thread::sleep(delay);
+                }
+                _ => return Err(error.into()),
             }
         }
-        
-        Ok(contributions)
     }
 }
+
+// GraphQL rejects contributionsCollection ranges longer than a year, so longer ranges are split
+// into windows of at most this many days, oldest first
+const WINDOW_DAYS: i64 = 365;
+
+fn year_windows(start_date: NaiveDate, end_date: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut windows = Vec::new();
+    let mut from = start_date;
+    while from <= end_date {
+        let to = (from + Duration::days(WINDOW_DAYS - 1)).min(end_date);
+        windows.push((from, to));
+        from = to + Duration::days(1);
+    }
+    windows
+}
+
+// Whether auto mode may use the public page after GraphQL failed: only when the connection or
+// GitHub itself failed. A bad token, a missing scope or an unknown user would fail there too,
+// or quietly hide private data, so those go to the caller
+fn can_fall_back(error: &anyhow::Error) -> bool {
+    for cause in error.chain() {
+        if let Some(error) = cause.downcast_ref::<GhHeatError>() {
+            return matches!(
+                error,
+                GhHeatError::Network(_) | GhHeatError::Timeout(_) | GhHeatError::ServerError { .. } | GhHeatError::RateLimit { .. }
+            );
+        }
+        if cause.is::<reqwest::Error>() {
+            return true;
+        }
+    }
+    false
+}
+
+// Error message for a feature that needs a token, listing the ways to provide one
+fn token_required(what: &str) -> String {
+    format!("A token is required for {}: set GH_TOKEN or GITHUB_TOKEN, run `gh auth login` or pass --token-file", what)
+}
+
+// Deserialize a JSON body, showing where it stopped making sense on failure
+fn parse_json<T: DeserializeOwned>(what: &str, body: &str) -> Result<T, GhHeatError> {
+    serde_json::from_str(body).map_err(|error| {
+        // serde_json reports a 1-based line and column
+        let offset = body.split_inclusive('\n')
+            .take(error.line().saturating_sub(1))
+            .map(str::len)
+            .sum::<usize>()
+            + error.column().saturating_sub(1);
+        GhHeatError::parse_failure(what, body, offset)
+    })
+}
@@ -0,0 +1,131 @@
+use crate::clock::Clock;
+use crate::error::GhHeatError;
+use chrono::{DateTime, Duration as ChronoDuration};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+// First delay when retrying a server error; it doubles with each attempt
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+
+// Longest delay between retries
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+// Wait for a secondary rate limit that doesn't say how long it lasts
+const SECONDARY_WAIT: Duration = Duration::from_secs(60);
+
+// Rate-limit and permission headers of a response
+#[derive(Debug, Default)]
+pub(super) struct Limits {
+    remaining: Option<u32>,
+    // Unix time when the primary limit resets
+    reset: Option<i64>,
+    // Seconds to wait, sent with secondary limits and some server errors
+    retry_after: Option<u64>,
+    // Scopes the endpoint accepts and scopes the token has, for classic tokens
+    accepted_scopes: Option<String>,
+    token_scopes: Option<String>,
+}
+
+impl Limits {
+    pub(super) fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers.get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_string())
+        };
+        Self {
+            remaining: header("x-ratelimit-remaining").and_then(|value| value.parse().ok()),
+            reset: header("x-ratelimit-reset").and_then(|value| value.parse().ok()),
+            retry_after: header("retry-after").and_then(|value| value.parse().ok()),
+            accepted_scopes: header("x-accepted-oauth-scopes").filter(|value| !value.is_empty()),
+            token_scopes: header("x-oauth-scopes"),
+        }
+    }
+
+    // The error for a refused request, telling rate limits apart from missing permissions.
+    // A 404 means the user doesn't exist when the URL names one
+    pub(super) fn error(&self, status: StatusCode, body: &str, clock: Clock, what: &str, user: Option<&str>) -> GhHeatError {
+        let message = github_message(body);
+        if status == StatusCode::UNAUTHORIZED {
+            return GhHeatError::Unauthorized(message.unwrap_or_else(|| "bad credentials".to_string()));
+        }
+        if status.is_server_error() {
+            return GhHeatError::ServerError { status: status.as_u16(), what: what.to_string() };
+        }
+        if let (StatusCode::NOT_FOUND, Some(user)) = (status, user) {
+            return GhHeatError::UserNotFound(user.to_string());
+        }
+
+        let mentions_limit = message.as_deref().is_some_and(|message| message.to_lowercase().contains("rate limit"));
+
+        let limited = status == StatusCode::TOO_MANY_REQUESTS
+            || (status == StatusCode::FORBIDDEN
+                && (self.remaining == Some(0) || self.retry_after.is_some() || mentions_limit));
+        if limited {
+            return self.rate_limit(clock);
+        }
+
+        if status == StatusCode::FORBIDDEN {
+            if let Some(missing) = self.missing_scopes() {
+                return GhHeatError::InsufficientScope(missing);
+            }
+            if let Some(message) = message.as_deref().filter(|message| message.contains("not accessible")) {
+                return GhHeatError::InsufficientScope(message.to_string());
+            }
+            if let Some(message) = message.as_deref().filter(|message| message.contains("suspended")) {
+                return GhHeatError::Unauthorized(message.to_string());
+            }
+        }
+
+        match message {
+            Some(message) => GhHeatError::Api(format!("Failed to fetch {}: {} ({})", what, status, message)),
+            None => GhHeatError::Api(format!("Failed to fetch {}: {}", what, status)),
+        }
+    }
+
+    // A rate limit error with the time it resets: Retry-After for secondary limits,
+    // X-RateLimit-Reset for the primary limit
+    pub(super) fn rate_limit(&self, clock: Clock) -> GhHeatError {
+        let secondary = self.remaining != Some(0);
+        let retry_at = match (self.retry_after, self.reset) {
+            (Some(seconds), _) => Some(clock.now_utc() + ChronoDuration::seconds(seconds as i64)),
+            (None, Some(reset)) if !secondary => DateTime::from_timestamp(reset, 0),
+            _ => None,
+        };
+
+        GhHeatError::RateLimit {
+            secondary,
+            retry_at: retry_at.map(|retry_at| clock.zone().convert(&retry_at)),
+        }
+    }
+
+    // How long to wait before retrying: only server errors and secondary rate limits are retried
+    pub(super) fn retry_delay(&self, error: &GhHeatError, attempt: u32) -> Option<Duration> {
+        let backoff = BACKOFF_BASE.saturating_mul(2u32.saturating_pow(attempt)).min(BACKOFF_MAX);
+        match error {
+            GhHeatError::RateLimit { secondary: true, .. } => {
+                Some(self.retry_after.map_or(SECONDARY_WAIT.max(backoff), Duration::from_secs))
+            }
+            GhHeatError::ServerError { .. } => Some(self.retry_after.map_or(backoff, Duration::from_secs)),
+            _ => None,
+        }
+    }
+
+    // Scopes the endpoint needs when the token has none of them
+    fn missing_scopes(&self) -> Option<String> {
+        let accepted = self.accepted_scopes.as_deref()?;
+        let granted: Vec<&str> = self.token_scopes.as_deref().unwrap_or("").split(',').map(str::trim).collect();
+        if accepted.split(',').map(str::trim).any(|scope| granted.contains(&scope)) {
+            return None;
+        }
+
+        Some(format!("needs one of: {}; token has: {}", accepted, self.token_scopes.as_deref().unwrap_or("none")))
+    }
+}
+
+// The "message" field of a JSON error body
+fn github_message(body: &str) -> Option<String> {
+    let body: serde_json::Value = serde_json::from_str(body).ok()?;
+    body.get("message")?.as_str().map(str::to_string)
+}
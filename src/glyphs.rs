@@ -0,0 +1,52 @@
+// Unicode glyphs used by the compact render modes
+
+// Upper half block, drawn with the top day as foreground and the bottom day as background
+pub const UPPER_HALF: &str = "\u{2580}";
+
+// Lower half block, used when only the bottom day has a color
+pub const LOWER_HALF: &str = "\u{2584}";
+
+// Quadrant glyphs indexed by bits: upper-left 1, upper-right 2, lower-left 4, lower-right 8
+const QUADRANTS: [char; 16] = [
+    ' ', '\u{2598}', '\u{259D}', '\u{2580}',
+    '\u{2596}', '\u{258C}', '\u{259E}', '\u{259B}',
+    '\u{2597}', '\u{259A}', '\u{2590}', '\u{259C}',
+    '\u{2584}', '\u{2599}', '\u{259F}', '\u{2588}',
+];
+
+// Braille dot bits for a 4x2 cell, indexed by [row][column]
+const BRAILLE_DOTS: [[u8; 2]; 4] = [
+    [0x01, 0x08],
+    [0x02, 0x10],
+    [0x04, 0x20],
+    [0x40, 0x80],
+];
+
+// Half block showing which of two stacked days are active, for output without colors
+pub fn half_block(top: bool, bottom: bool) -> &'static str {
+    match (top, bottom) {
+        (true, true) => "\u{2588}",
+        (true, false) => UPPER_HALF,
+        (false, true) => LOWER_HALF,
+        (false, false) => " ",
+    }
+}
+
+// Bit for a dot at the given row and column of a quadrant or braille glyph
+pub fn dot_bit(braille: bool, row: usize, col: usize) -> u8 {
+    if braille {
+        BRAILLE_DOTS[row][col]
+    } else {
+        1 << (row * 2 + col)
+    }
+}
+
+// Quadrant glyph for a set of dot bits
+pub fn quadrant(dots: u8) -> char {
+    QUADRANTS[(dots & 0x0F) as usize]
+}
+
+// Braille glyph for a set of dot bits
+pub fn braille(dots: u8) -> char {
+    char::from_u32(0x2800 + dots as u32).unwrap_or(' ')
+}
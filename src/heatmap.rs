@@ -1,17 +1,162 @@
-use chrono::{Datelike, NaiveDate, Utc, Weekday};
-use colored::{ColoredString, Colorize};
+use crate::glyphs;
+use crate::locale::{self, Locale};
+use crate::overlay::{self, Overlays};
+use crate::term::ColorSupport;
+use crate::theme::Theme;
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::fmt::Write;
+
+mod diff;
+mod vertical;
+
+// Width of the weekday label column
+const LABEL_WIDTH: usize = 4;
+
+// How days are drawn in the grid
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Colored blocks, two columns per day
+    Blocks,
+    /// ASCII symbols, two columns per day
+    Symbols,
+    /// Contribution counts, two columns per day
+    Numbers,
+    /// Half blocks, two days per character
+    Half,
+    /// Quadrant glyphs, 2x2 days per character
+    Quadrant,
+    /// Braille dots, 2x4 days per character
+    Braille,
+}
+
+impl RenderMode {
+    // Whether the mode packs several days into one character
+    pub fn is_compact(self) -> bool {
+        matches!(self, RenderMode::Half | RenderMode::Quadrant | RenderMode::Braille)
+    }
+    
+    // Number of weekdays packed into one terminal row
+    fn days_per_row(self) -> usize {
+        match self {
+            RenderMode::Half | RenderMode::Quadrant => 2,
+            RenderMode::Braille => 4,
+            _ => 1,
+        }
+    }
+    
+    // Number of weeks packed into one glyph
+    fn weeks_per_glyph(self) -> usize {
+        match self {
+            RenderMode::Quadrant | RenderMode::Braille => 2,
+            _ => 1,
+        }
+    }
+    
+    // Terminal columns taken by one glyph
+    fn glyph_width(self) -> usize {
+        if self.is_compact() { 1 } else { 2 }
+    }
+    
+    // Terminal columns needed for a number of weeks
+    pub fn columns(self, weeks: usize) -> usize {
+        weeks.div_ceil(self.weeks_per_glyph()) * self.glyph_width()
+    }
+    
+    // Column offset of a week from the start of the grid
+    fn columns_before(self, week_idx: usize) -> usize {
+        week_idx / self.weeks_per_glyph() * self.glyph_width()
+    }
+}
+
+// First day of the week, shown in the top row of the grid
+#[derive(clap::ValueEnum, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    Sunday,
+    Monday,
+    Saturday,
+}
+
+impl WeekStart {
+    pub fn weekday(self) -> Weekday {
+        match self {
+            WeekStart::Sunday => Weekday::Sun,
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Saturday => Weekday::Sat,
+        }
+    }
+}
+
+// A run of consecutive days
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: u32,
+}
+
+// Streaks of days with contributions and the longest gap between them
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Streaks {
+    pub current: Option<Span>,
+    pub longest: Option<Span>,
+    pub longest_gap: Option<Span>,
+}
+
+// Direction in which weeks advance
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// Weeks run left to right, weekdays top to bottom
+    Horizontal,
+    /// Weeks run top to bottom, weekdays left to right
+    Vertical,
+}
+
+// What to do when the grid is wider than the terminal
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fit {
+    /// Split the grid into stacked bands
+    Wrap,
+    /// Switch to a compact glyph mode, wrapping if none fits
+    Compact,
+    /// Never adjust the layout
+    None,
+}
+
+// Options controlling how the heatmap is drawn
+#[derive(Clone, Copy)]
+pub struct RenderOptions<'a> {
+    pub theme: &'a Theme,
+    pub colors: ColorSupport,
+    pub mode: RenderMode,
+    // Maximum line width; wider grids are split into bands
+    pub max_width: Option<usize>,
+    // Show ISO week numbers below the month headers
+    pub week_numbers: bool,
+    // Language of month, weekday and legend labels
+    pub locale: &'a Locale,
+    pub orientation: Orientation,
+    // Day drawn with a cursor, in the interactive view
+    pub cursor: Option<NaiveDate>,
+    // Days drawn with a marker, like new contributions in watch mode
+    pub highlights: &'a [NaiveDate],
+    // Today, weekends, holidays and custom markers drawn over the grid
+    pub overlays: &'a Overlays,
+}
 
 // Struct to generate and render contribution heatmaps
 pub struct Heatmap {
     contributions: HashMap<NaiveDate, u32>,
     date_range: (NaiveDate, NaiveDate),
+    week_start: Weekday,
 }
 
 impl Heatmap {
-    pub fn new(contributions: HashMap<NaiveDate, u32>) -> Self {
+    pub fn new(contributions: HashMap<NaiveDate, u32>, week_start: WeekStart, today: NaiveDate) -> Self {
         // Find the earliest and latest dates
-        let mut earliest = Utc::now().naive_utc().date();
+        let mut earliest = today;
         let mut latest = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
         
         for date in contributions.keys() {
@@ -25,20 +170,49 @@ impl Heatmap {
         
         // If no contributions, use current date
         if contributions.is_empty() {
-            let today = Utc::now().naive_utc().date();
             earliest = today;
             latest = today;
         }
         
-        // Adjust earliest date to start from Sunday for better alignment
-        while earliest.weekday() != Weekday::Sun {
-            earliest = earliest.pred_opt().unwrap_or(earliest);
+        Self::with_date_range(contributions, week_start, earliest, latest)
+    }
+    
+    // Create a heatmap over a fixed date range, so several heatmaps can share one calendar
+    pub fn with_date_range(
+        contributions: HashMap<NaiveDate, u32>,
+        week_start: WeekStart,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Self {
+        let week_start = week_start.weekday();
+        Self {
+            contributions,
+            date_range: (Self::week_containing(start_date, week_start), end_date),
+            week_start,
         }
+    }
+    
+    // The same heatmap limited to the days from start_date to end_date
+    pub fn between(&self, start_date: NaiveDate, end_date: NaiveDate) -> Self {
+        let contributions = self.contributions.iter()
+            .filter(|(date, _)| **date >= start_date && **date <= end_date)
+            .map(|(date, count)| (*date, *count))
+            .collect();
         
         Self {
             contributions,
-            date_range: (earliest, latest),
+            date_range: (Self::week_containing(start_date, self.week_start), end_date),
+            week_start: self.week_start,
+        }
+    }
+    
+    // First day of the week containing a date, so the grid starts on a full week
+    pub fn week_containing(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+        let mut earliest = date;
+        while earliest.weekday() != week_start {
+            earliest = earliest.pred_opt().unwrap_or(earliest);
         }
+        earliest
     }
     
     // Calculate total number of contributions
@@ -56,42 +230,212 @@ impl Heatmap {
         *self.contributions.values().max().unwrap_or(&0)
     }
     
-    // Print a horizontal border with optional message
-    fn print_border(&self, width: usize, msg: &str) {
-        for _ in 0..width {
-            print!("=");
+    // Average number of contributions on days with at least one
+    pub fn average_on_active_days(&self) -> f64 {
+        let active_days = self.active_days();
+        if active_days > 0 {
+            self.total_contributions() as f64 / active_days as f64
+        } else {
+            0.0
         }
-        print!("{}", msg);
-        println!();
     }
-
+    
+    // First day of the week in the grid
+    pub fn week_start(&self) -> Weekday {
+        self.week_start
+    }
+    
+    // First and last day of the grid
+    pub fn date_range(&self) -> (NaiveDate, NaiveDate) {
+        self.date_range
+    }
+    
+    // Contribution counts by date
+    pub fn contributions(&self) -> &HashMap<NaiveDate, u32> {
+        &self.contributions
+    }
+    
+    // Compute the current and longest streaks and the longest gap,
+    // optionally skipping weekends so they neither extend nor break a streak
+    pub fn streaks(&self, weekdays_only: bool) -> Streaks {
+        let mut runs: Vec<(bool, Span)> = Vec::new();
+        
+        let skipped = |date: NaiveDate| weekdays_only && matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        
+        // Group consecutive days into runs of active and inactive days, from the first active one
+        let first_active = self.contributions.iter()
+            .filter(|(date, count)| **count > 0 && !skipped(**date))
+            .map(|(date, _)| *date)
+            .min();
+        let (first_date, last_date) = match first_active {
+            Some(first_date) => (first_date, self.date_range.1),
+            None => return Streaks { current: None, longest: None, longest_gap: None },
+        };
+        for date in first_date.iter_days().take_while(|date| *date <= last_date) {
+            if skipped(date) {
+                continue;
+            }
+            
+            let active = self.count(date) > 0;
+            match runs.last_mut() {
+                Some((run_active, span)) if *run_active == active => {
+                    span.end = date;
+                    span.days += 1;
+                }
+                _ => runs.push((active, Span { start: date, end: date, days: 1 })),
+            }
+        }
+        
+        // Earliest run wins ties
+        let longest_of = |active: bool| {
+            runs.iter()
+                .filter(|(run_active, _)| *run_active == active)
+                .fold(None::<Span>, |best, (_, span)| match best {
+                    Some(best) if best.days >= span.days => Some(best),
+                    _ => Some(*span),
+                })
+        };
+        
+        // The current streak survives a last day without contributions yet
+        let current = match runs.as_slice() {
+            [.., (true, span)] => Some(*span),
+            [.., (true, span), (false, gap)] if gap.days == 1 => Some(*span),
+            _ => None,
+        };
+        
+        Streaks {
+            current,
+            longest: longest_of(true),
+            longest_gap: longest_of(false),
+        }
+    }
+    
+    // Number of week columns in the grid
+    pub fn weeks(&self) -> usize {
+        self.create_grid().len()
+    }
+    
+    // Pick the first mode, from the requested one to the most compact, that fits the width
+    pub fn fitting_mode(&self, mode: RenderMode, max_width: usize) -> RenderMode {
+        let weeks = self.weeks();
+        let candidates = [mode, RenderMode::Half, RenderMode::Quadrant, RenderMode::Braille];
+        
+        candidates.into_iter()
+            .filter(|candidate| *candidate == mode || !mode.is_compact())
+            .find(|candidate| LABEL_WIDTH + candidate.columns(weeks) <= max_width)
+            .unwrap_or(mode)
+    }
+    
     // Render the heatmap to the terminal
-    pub fn render(&self, dark_mode: bool, use_symbols: bool, use_numbers: bool) {
-        println!(); // Add some spacing
+    pub fn render(&self, options: &RenderOptions) {
+        print!("{}", self.render_to_string(options));
+    }
+    
+    // Render the heatmap into a string
+    pub fn render_to_string(&self, options: &RenderOptions) -> String {
+        let mut out = String::new();
+        out.push('\n'); // Add some spacing
+        
+        match options.orientation {
+            Orientation::Horizontal => self.write_horizontal(&mut out, options),
+            Orientation::Vertical => self.write_vertical(&mut out, options),
+        }
         
-        // Create grid and determine its width
+        out.push('\n'); // Add some spacing
+        self.write_key(&mut out, options);
+        self.write_overlay_legend(&mut out, options);
+        
+        out
+    }
+    
+    // Write the grid with weeks as columns, wrapped into bands
+    fn write_horizontal(&self, out: &mut String, options: &RenderOptions) {
+        self.write_bands(out, options, |out, band| self.write_grid(out, band, options));
+    }
+    
+    // Write month headers and borders around bands of weeks, drawing each band with write_band
+    fn write_bands<F>(&self, out: &mut String, options: &RenderOptions, mut write_band: F)
+    where
+        F: FnMut(&mut String, &[Vec<NaiveDate>]),
+    {
+        // Create grid and split it into bands that fit the terminal
         let grid = self.create_grid();
-        let grid_width = grid.len();
+        let bands = Self::split_bands(&grid, options);
+        let band_columns = bands.iter()
+            .map(|band| options.mode.columns(band.len()))
+            .max()
+            .unwrap_or(0);
+        let mut width = LABEL_WIDTH + band_columns;
         
-        // Print month headers and calculate width for borders
-        let header_width = self.print_month_headers(grid_width);
+        // Date range shown after the top border, shortening the border if needed
+        let date_range_msg = self.date_range_msg();
+        if let Some(max_width) = options.max_width {
+            width = width.min(max_width.saturating_sub(date_range_msg.len()).max(LABEL_WIDTH));
+        }
         
-        // Print top border with date range
-        let (start_date, end_date) = self.date_range;
-        let date_range_msg = format!("  {}-{}", 
-                                     start_date.format("%Y-%m-%d"), 
-                                     end_date.format("%Y-%m-%d"));
-        self.print_border(header_width, &date_range_msg);
+        for (band_idx, band) in bands.iter().enumerate() {
+            // Each band gets its own month headers
+            if band_idx > 0 {
+                out.push('\n');
+            }
+            self.write_month_headers(out, band, options);
+            if options.week_numbers {
+                self.write_week_numbers(out, band, options.mode);
+            }
+            if band_idx == 0 {
+                Self::write_border(out, width, &date_range_msg);
+            }
+            
+            // Weekday labels and heatmap grid
+            write_band(out, band);
+        }
         
-        // Print weekday labels and heatmap grid
-        self.print_grid(&grid, dark_mode, use_symbols, use_numbers);
+        Self::write_border(out, width, "");
+    }
+    
+    // Split the grid into bands of whole weeks, preferring to break at quarters and months
+    fn split_bands<'g>(grid: &'g [Vec<NaiveDate>], options: &RenderOptions) -> Vec<&'g [Vec<NaiveDate>]> {
+        let capacity = match options.max_width {
+            Some(max_width) => {
+                let glyphs = max_width.saturating_sub(LABEL_WIDTH) / options.mode.glyph_width();
+                (glyphs * options.mode.weeks_per_glyph()).max(options.mode.weeks_per_glyph())
+            }
+            None => return vec![grid],
+        };
         
-        // Print bottom border
-        self.print_border(header_width, "");
-        println!(); // Add some spacing
+        let mut bands = Vec::new();
+        let mut rest = grid;
+        while rest.len() > capacity {
+            // Weeks that start a new month, within the capacity of this band
+            let boundaries: Vec<usize> = (1..=capacity)
+                .filter(|&idx| rest[idx][0].month() != rest[idx - 1][0].month())
+                .collect();
+            let quarter = boundaries.iter()
+                .rev()
+                .find(|&&idx| idx >= capacity / 2 && rest[idx][0].month() % 3 == 1);
+            
+            let split = quarter
+                .or_else(|| boundaries.last())
+                .copied()
+                .unwrap_or(capacity);
+            let (band, remainder) = rest.split_at(split);
+            bands.push(band);
+            rest = remainder;
+        }
+        bands.push(rest);
         
-        // Print color/symbol key
-        self.print_key(dark_mode, use_symbols, use_numbers);
+        bands
+    }
+    
+    // Date range message shown after the top border
+    fn date_range_msg(&self) -> String {
+        let (start_date, end_date) = self.date_range;
+        format!("  {}-{}", start_date.format("%Y-%m-%d"), end_date.format("%Y-%m-%d"))
+    }
+    
+    // Write a horizontal border with optional message
+    fn write_border(out: &mut String, width: usize, msg: &str) {
+        let _ = writeln!(out, "{}{}", "=".repeat(width), msg);
     }
     
     // Create grid structure
@@ -105,7 +449,7 @@ impl Heatmap {
         
         while current_date <= end_date {
             current_week.push(current_date);
-            if current_date.weekday() == Weekday::Sat {
+            if current_date.weekday() == self.week_start.pred() {
                 grid.push(current_week);
                 current_week = Vec::new();
             }
@@ -124,104 +468,164 @@ impl Heatmap {
         grid
     }
     
-    // Print the month headers above the heatmap
-    fn print_month_headers(&self, grid_width: usize) -> usize {
-        let (start_date, end_date) = self.date_range;
-        let mut current_date = start_date;
-        let mut current_month = current_date.month();
-        let mut month_positions = Vec::new();
-        let mut position = 0;
+    // Write the month headers above the heatmap
+    fn write_month_headers(&self, out: &mut String, grid: &[Vec<NaiveDate>], options: &RenderOptions) {
+        let mode = options.mode;
+        let mut labels = Vec::new();
+        let mut previous_month = None;
         
-        // Calculate month positions
-        while current_date <= end_date {
-            if current_date.month() != current_month {
-                let month_name = Self::month_name(current_month);
-                month_positions.push((position, month_name));
-                current_month = current_date.month();
+        // Label each month above the first week that starts in it
+        for (week_idx, week) in grid.iter().enumerate() {
+            let month = week[0].month();
+            if previous_month != Some(month) {
+                labels.push((mode.columns_before(week_idx), options.locale.month(month).to_string()));
+                previous_month = Some(month);
             }
-            if current_date.weekday() == Weekday::Sun {
-                position += 1;
-            }
-            current_date = current_date.succ_opt().unwrap_or(current_date);
         }
         
-        // Add the last month
-        month_positions.push((position, Self::month_name(current_month)));
+        Self::write_header_labels(out, &labels, mode.columns(grid.len()));
+    }
+    
+    // Write the ISO week number of each column above the heatmap
+    fn write_week_numbers(&self, out: &mut String, grid: &[Vec<NaiveDate>], mode: RenderMode) {
+        // A column's ISO week is the week of the Monday it contains
+        let labels: Vec<(usize, String)> = grid.iter()
+            .enumerate()
+            .filter_map(|(week_idx, week)| {
+                Self::iso_week(week).map(|number| (mode.columns_before(week_idx), format!("{:>2}", number)))
+            })
+            .collect();
         
-        // Print month names
-        print!("    "); // Space for weekday labels (reduced by 1)
-        let mut last_pos = 0;
-        for (pos, name) in month_positions {
-            let spaces = pos - last_pos;
-            if spaces > 0 {
-                print!("{}{}", " ".repeat(spaces * 2 - spaces), name); // Adjusted spacing
+        Self::write_header_labels(out, &labels, mode.columns(grid.len()));
+    }
+    
+    // ISO week number of the Monday in a week
+    fn iso_week(week: &[NaiveDate]) -> Option<u32> {
+        week.iter()
+            .find(|date| date.weekday() == Weekday::Mon)
+            .map(|monday| monday.iso_week().week())
+    }
+    
+    // Write a header line with labels at grid columns, skipping labels that would collide
+    fn write_header_labels(out: &mut String, labels: &[(usize, String)], grid_columns: usize) {
+        let mut line = " ".repeat(LABEL_WIDTH);
+        let mut line_width = LABEL_WIDTH;
+        
+        // Positions are measured in display columns so double-width labels stay aligned
+        for (offset, label) in labels {
+            let column = LABEL_WIDTH + offset;
+            let label_width = locale::display_width(label);
+            let gap = if line_width > LABEL_WIDTH { 1 } else { 0 };
+            if column < line_width + gap || column + label_width > LABEL_WIDTH + grid_columns + 1 {
+                continue;
             }
-            last_pos = pos + name.len() / 2;
+            line.push_str(&" ".repeat(column - line_width));
+            line.push_str(label);
+            line_width = column + label_width;
         }
-        println!();
-        
-        // Calculate width based on actual grid size
-        // Make sure it's at least as wide as needed for the grid + some padding
-        let min_width = 4 + (grid_width * 2); // 4 for labels + 2 chars per week
-        let current_width = 4 + (position * 2 - position) + 10;
-        
-        std::cmp::max(min_width, current_width)
-    }
-    
-    // Print the weekday labels and contribution grid
-    fn print_grid(&self, grid: &[Vec<NaiveDate>], dark_mode: bool, use_symbols: bool, use_numbers: bool) {
-        // Print the grid transposed (days as rows)
-        for day_idx in 0..7 {
-            // Print weekday label
-            if day_idx == 1 {
-                print!("Mon ");
-            } else if day_idx == 3 {
-                print!("Wed ");
-            } else if day_idx == 5 {
-                print!("Fri ");
-            } else {
-                print!("    ");
-            }
+        
+        let _ = writeln!(out, "{}", line);
+    }
+    
+    // Write the weekday labels and contribution grid
+    fn write_grid(&self, out: &mut String, grid: &[Vec<NaiveDate>], options: &RenderOptions) {
+        let days_per_row = options.mode.days_per_row();
+        let weeks_per_glyph = options.mode.weeks_per_glyph();
+        
+        // Print the grid transposed (days as rows), packing days into glyphs
+        for first_day in (0..7).step_by(days_per_row) {
+            let days = first_day..(first_day + days_per_row).min(7);
             
-            // Print cells for each week
-            for week in grid {
-                if day_idx < week.len() {
-                    let date = week[day_idx];
-                    let count = self.contributions.get(&date).unwrap_or(&0);
-                    
-                    if use_numbers {
-                        print!("{:2}", count); // Removed space
-                    } else {
-                        let cell = self.format_cell(*count, dark_mode, use_symbols);
-                        print!("{}", cell); // Removed space
-                    }
+            // Label the row with the first labeled weekday it contains
+            let label = days.clone()
+                .map(|day_idx| self.weekday_of_row(day_idx))
+                .find(|weekday| matches!(weekday, Weekday::Mon | Weekday::Wed | Weekday::Fri))
+                .map_or("", |weekday| options.locale.weekday(weekday));
+            out.push_str(&locale::pad(label, LABEL_WIDTH));
+            
+            // Write one glyph per group of weeks
+            for weeks in grid.chunks(weeks_per_glyph) {
+                if !options.mode.is_compact() {
+                    out.push_str(&self.format_day(weeks[0][first_day], options));
+                    continue;
                 }
+                let block: Vec<Vec<Option<u32>>> = days.clone()
+                    .map(|day_idx| {
+                        (0..weeks_per_glyph)
+                            .map(|offset| weeks.get(offset).map(|week| self.count(week[day_idx])))
+                            .collect()
+                    })
+                    .collect();
+                out.push_str(&self.format_glyph(&block, options));
             }
-            println!();
+            out.push('\n');
         }
     }
     
-    // Print legend/key for the heatmap
-    fn print_key(&self, dark_mode: bool, use_symbols: bool, use_numbers: bool) {
-        if use_numbers {
+    // Weekday shown in a row of the grid
+    fn weekday_of_row(&self, day_idx: usize) -> Weekday {
+        (0..day_idx).fold(self.week_start, |weekday, _| weekday.succ())
+    }
+    
+    // Contribution count for a single day
+    fn count(&self, date: NaiveDate) -> u32 {
+        *self.contributions.get(&date).unwrap_or(&0)
+    }
+    
+    // Write legend/key for the heatmap
+    fn write_key(&self, out: &mut String, options: &RenderOptions) {
+        if options.mode == RenderMode::Numbers {
             return; // No key needed for numbers
         }
+        if options.mode.is_compact() && options.colors == ColorSupport::None {
+            return; // Compact glyphs only show activity without colors
+        }
         
-        print!("  Less ");
+        let _ = write!(out, "  {} ", options.locale.less);
         
         // Show the full gradient range
         let counts = [0, 4, 8, 12, 16, 20]; // Representing each intensity level
         for count in counts {
-            let cell = self.format_cell(count, dark_mode, use_symbols);
-            print!("{}", cell);
+            if options.mode.is_compact() {
+                let rgb = match Self::intensity(count) {
+                    0 => options.theme.empty,
+                    intensity => Some(options.theme.color(intensity)),
+                };
+                let glyph = if rgb.is_some() { "\u{2588}" } else { " " };
+                out.push_str(&options.colors.paint(glyph, rgb, None));
+            } else {
+                out.push_str(&self.format_cell(count, options));
+            }
         }
         
-        println!(" More");
+        let _ = writeln!(out, " {}", options.locale.more);
     }
     
-    // Format a cell based on contribution count and preferences
-    fn format_cell(&self, count: u32, dark_mode: bool, use_symbols: bool) -> ColoredString {
-        let intensity = if count == 0 {
+    // List the holidays and custom markers shown in the grid. Compact glyphs pack several days
+    // into a character and can't show the marks, so they get no legend
+    fn write_overlay_legend(&self, out: &mut String, options: &RenderOptions) {
+        if options.mode.is_compact() {
+            return;
+        }
+        let (start_date, end_date) = self.date_range;
+        let overlays = options.overlays;
+        let marked = [("{}", &overlays.markers), ("::", &overlays.holidays)];
+        
+        for (marker, days) in marked {
+            for span in overlay::spans(days, start_date, end_date) {
+                let dates = if span.start == span.end {
+                    span.start.format("%Y-%m-%d").to_string()
+                } else {
+                    format!("{} - {}", span.start.format("%Y-%m-%d"), span.end.format("%Y-%m-%d"))
+                };
+                let _ = writeln!(out, "  {} {} {}", marker, dates, span.label);
+            }
+        }
+    }
+    
+    // Map a contribution count to an intensity level from 0 to 5
+    fn intensity(count: u32) -> usize {
+        if count == 0 {
             0
         } else if count < 5 {
             1
@@ -233,8 +637,121 @@ impl Heatmap {
             4
         } else {
             5
+        }
+    }
+    
+    // Format a group of days (rows of weekdays x columns of weeks) as one glyph
+    fn format_glyph(&self, block: &[Vec<Option<u32>>], options: &RenderOptions) -> String {
+        match options.mode {
+            RenderMode::Blocks | RenderMode::Symbols | RenderMode::Numbers => {
+                block[0][0].map_or_else(String::new, |count| self.format_cell(count, options))
+            }
+            RenderMode::Half => {
+                let color = |count: Option<u32>| match count.map(Self::intensity) {
+                    Some(0) => options.theme.empty,
+                    Some(intensity) => Some(options.theme.color(intensity)),
+                    None => None,
+                };
+                let top = block[0][0];
+                let bottom = block.get(1).and_then(|row| row[0]);
+                
+                if options.colors == ColorSupport::None {
+                    let active = |count: Option<u32>| count.unwrap_or(0) > 0;
+                    return glyphs::half_block(active(top), active(bottom)).to_string();
+                }
+                match (color(top), color(bottom)) {
+                    (Some(upper), lower) => options.colors.paint(glyphs::UPPER_HALF, Some(upper), lower),
+                    (None, Some(lower)) => options.colors.paint(glyphs::LOWER_HALF, Some(lower), None),
+                    (None, None) => " ".to_string(),
+                }
+            }
+            RenderMode::Quadrant | RenderMode::Braille => {
+                // Dots mark active days, colored by the busiest day in the group
+                let mut dots = 0u8;
+                let mut max_count = 0;
+                for (row_idx, row) in block.iter().enumerate() {
+                    for (col_idx, count) in row.iter().enumerate() {
+                        let count = count.unwrap_or(0);
+                        if count > 0 {
+                            dots |= glyphs::dot_bit(options.mode == RenderMode::Braille, row_idx, col_idx);
+                            max_count = max_count.max(count);
+                        }
+                    }
+                }
+                
+                let glyph = if options.mode == RenderMode::Braille {
+                    glyphs::braille(dots)
+                } else {
+                    glyphs::quadrant(dots)
+                };
+                let fg = if max_count > 0 { Some(options.theme.color(Self::intensity(max_count))) } else { None };
+                options.colors.paint(&glyph.to_string(), fg, options.theme.empty)
+            }
+        }
+    }
+    
+    // Format a cell based on contribution count and preferences
+    fn format_cell(&self, count: u32, options: &RenderOptions) -> String {
+        if options.mode == RenderMode::Numbers {
+            return format!("{:2}", count);
+        }
+        
+        Self::format_level(Self::intensity(count), options)
+    }
+    
+    // Format a single day with its cursor, highlight or overlay marker, dimming weekends
+    fn format_day(&self, date: NaiveDate, options: &RenderOptions) -> String {
+        let overlays = options.overlays;
+        let marker = if options.cursor == Some(date) {
+            Some("[]")
+        } else if options.mode == RenderMode::Numbers {
+            None // Other markers would hide the count
+        } else if options.highlights.contains(&date) {
+            Some("<>")
+        } else if overlays.today == Some(date) {
+            Some("()")
+        } else if overlays.markers.contains_key(&date) {
+            Some("{}")
+        } else if overlays.holidays.contains_key(&date) {
+            Some("::")
+        } else {
+            None
         };
         
+        // Weekends are drawn with a muted copy of the theme
+        let dimmed_theme;
+        let dimmed_options;
+        let options = if overlays.dim_weekends && matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+            dimmed_theme = options.theme.dimmed();
+            dimmed_options = RenderOptions { theme: &dimmed_theme, ..*options };
+            &dimmed_options
+        } else {
+            options
+        };
+        
+        match marker {
+            Some(marker) => Self::format_marker(self.count(date), marker, options),
+            None => self.format_cell(self.count(date), options),
+        }
+    }
+    
+    // Format a marked cell as two marker characters over the day's color
+    fn format_marker(count: u32, marker: &str, options: &RenderOptions) -> String {
+        let rgb = match Self::intensity(count) {
+            0 => options.theme.empty,
+            intensity => Some(options.theme.color(intensity)),
+        };
+        
+        match options.mode {
+            RenderMode::Blocks => options.colors.paint(marker, None, rgb),
+            _ => options.colors.paint(marker, rgb, None),
+        }
+    }
+    
+    // Format a two-column cell for an intensity level from 0 to 5 as a colored block or symbol
+    pub fn format_level(intensity: usize, options: &RenderOptions) -> String {
+        let use_symbols = options.mode == RenderMode::Symbols;
+        
         let text = if use_symbols {
             match intensity {
                 0 => "  ",
@@ -248,54 +765,17 @@ impl Heatmap {
             "  " // Two spaces for colored blocks
         };
         
+        let rgb = if intensity == 0 {
+            options.theme.empty
+        } else {
+            Some(options.theme.color(intensity))
+        };
+        
         if use_symbols {
-            match intensity {
-                0 => text.normal(),
-                1 => text.bright_black(),
-                2 => text.blue(),
-                3 => text.green(),
-                4 => text.yellow(),
-                _ => text.red(),
-            }
-        } else if dark_mode {
-            // Red gradient (dark mode)
-            match intensity {
-                0 => text.normal(),
-                1 => text.on_truecolor(59, 0, 0),
-                2 => text.on_truecolor(102, 0, 0),
-                3 => text.on_truecolor(157, 0, 0),
-                4 => text.on_truecolor(204, 0, 0),
-                _ => text.on_truecolor(255, 0, 0),
-            }
+            let fg = if intensity == 0 { None } else { rgb };
+            options.colors.paint(text, fg, None)
         } else {
-            // Green gradient (light mode)
-            match intensity {
-                0 => text.normal(),
-                1 => text.on_truecolor(220, 247, 220),
-                2 => text.on_truecolor(153, 237, 153),
-                3 => text.on_truecolor(85, 219, 85),
-                4 => text.on_truecolor(44, 160, 44),
-                _ => text.on_truecolor(0, 109, 0),
-            }
-        }
-    }
-    
-    // Helper to get month name from month number
-    fn month_name(month: u32) -> &'static str {
-        match month {
-            1 => "Jan",
-            2 => "Feb",
-            3 => "Mar",
-            4 => "Apr",
-            5 => "May",
-            6 => "Jun",
-            7 => "Jul",
-            8 => "Aug",
-            9 => "Sep",
-            10 => "Oct",
-            11 => "Nov",
-            12 => "Dec",
-            _ => "",
+            options.colors.paint(text, None, rgb)
         }
     }
 }
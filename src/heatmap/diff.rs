@@ -0,0 +1,99 @@
+use super::{Heatmap, RenderMode, RenderOptions, LABEL_WIDTH};
+use crate::locale;
+use crate::term::ColorSupport;
+use crate::theme::{DIVERGING_FEWER, DIVERGING_MORE};
+use chrono::{NaiveDate, Weekday};
+use std::fmt::Write;
+
+impl Heatmap {
+    // Render the day-by-day difference from this heatmap to another one on the same calendar,
+    // with a legend naming the side each color favors
+    pub fn render_difference(&self, other: &Heatmap, options: &RenderOptions, labels: (&str, &str)) -> String {
+        let mut out = String::new();
+
+        // Compact glyphs and numbers cannot show a signed level, so draw plain cells instead
+        let mode = match (options.mode, options.colors) {
+            (_, ColorSupport::None) | (RenderMode::Symbols, _) => RenderMode::Symbols,
+            _ => RenderMode::Blocks,
+        };
+        let options = RenderOptions { mode, ..*options };
+
+        out.push('\n');
+        self.write_bands(&mut out, &options, |out, band| self.write_difference_grid(out, other, band, &options));
+        out.push('\n');
+
+        // Legend from the strongest lead of the first side to the strongest lead of the second
+        let _ = write!(out, "  {} ", labels.0);
+        for level in (1..=3).rev() {
+            out.push_str(&Self::format_difference(-level, &options));
+        }
+        out.push_str(&Self::format_difference(0, &options));
+        for level in 1..=3 {
+            out.push_str(&Self::format_difference(level, &options));
+        }
+        let _ = writeln!(out, " {}", labels.1);
+
+        out
+    }
+
+    // Write the weekday labels and the signed difference of each day both heatmaps cover
+    fn write_difference_grid(&self, out: &mut String, other: &Heatmap, grid: &[Vec<NaiveDate>], options: &RenderOptions) {
+        for day_idx in 0..7 {
+            let weekday = self.weekday_of_row(day_idx);
+            let label = match weekday {
+                Weekday::Mon | Weekday::Wed | Weekday::Fri => options.locale.weekday(weekday),
+                _ => "",
+            };
+            out.push_str(&locale::pad(label, LABEL_WIDTH));
+
+            // Days outside either period, like the rest of the current year, stay blank
+            for week in grid {
+                let date = week[day_idx];
+                match (self.contributions.get(&date), other.contributions.get(&date)) {
+                    (Some(count), Some(other_count)) => {
+                        let difference = *other_count as i64 - *count as i64;
+                        out.push_str(&Self::format_difference(Self::difference_level(difference), options));
+                    }
+                    _ => out.push_str("  "),
+                }
+            }
+            out.push('\n');
+        }
+    }
+
+    // Map a difference in contributions to a signed level from -3 to 3
+    fn difference_level(difference: i64) -> i64 {
+        let level = match difference.abs() {
+            0 => 0,
+            1..=4 => 1,
+            5..=9 => 2,
+            _ => 3,
+        };
+        level * difference.signum()
+    }
+
+    // Format a two-column cell for a signed difference level as a colored block or symbol
+    fn format_difference(level: i64, options: &RenderOptions) -> String {
+        let rgb = match level {
+            0 => options.theme.empty,
+            level if level < 0 => Some(DIVERGING_FEWER[level.unsigned_abs() as usize - 1]),
+            level => Some(DIVERGING_MORE[level as usize - 1]),
+        };
+
+        if options.mode == RenderMode::Symbols {
+            let text = match level {
+                -3 => "<<",
+                -2 => "--",
+                -1 => "- ",
+                0 => "  ",
+                1 => "+ ",
+                2 => "++",
+                _ => ">>",
+            };
+            let fg = if level == 0 { None } else { rgb };
+            options.colors.paint(text, fg, None)
+        } else {
+            options.colors.paint("  ", None, rgb)
+        }
+    }
+}
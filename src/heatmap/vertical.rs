@@ -0,0 +1,109 @@
+use super::{Heatmap, RenderOptions};
+use crate::locale;
+use chrono::Datelike;
+use std::fmt::Write;
+
+// Width of the left gutter holding month labels, e.g. "2025 Jan"
+const GUTTER_WIDTH: usize = 10;
+
+impl Heatmap {
+    // Write the grid with weeks as rows, weekdays as columns and months in a left gutter
+    pub(super) fn write_vertical(&self, out: &mut String, options: &RenderOptions) {
+        let grid = self.create_grid();
+        let mode = options.mode;
+
+        // Compact modes pack consecutive weeks into one row and weekdays into one glyph
+        let weeks_per_row = mode.days_per_row();
+        let days_per_glyph = mode.weeks_per_glyph();
+        let width = GUTTER_WIDTH + mode.columns(7);
+
+        self.write_weekday_header(out, options);
+        Self::write_border(out, width, &self.date_range_msg());
+
+        let mut previous_month = None;
+        let mut previous_year = None;
+        for weeks in grid.chunks(weeks_per_row) {
+            // Label the row where a month starts, adding the year when it changes
+            let starts_month = weeks.iter()
+                .map(|week| week[0])
+                .find(|date| previous_month != Some((date.year(), date.month())));
+            let label = match starts_month {
+                Some(date) => {
+                    previous_month = Some((date.year(), date.month()));
+                    let month = options.locale.month(date.month());
+                    if previous_year != Some(date.year()) {
+                        previous_year = Some(date.year());
+                        format!("{} {}", date.year(), month)
+                    } else {
+                        format!("     {}", month)
+                    }
+                }
+                None => String::new(),
+            };
+            out.push_str(&locale::pad(&label, GUTTER_WIDTH));
+
+            // One glyph per group of weekdays, covering every week in the row
+            for first_day in (0..7).step_by(days_per_glyph) {
+                if !mode.is_compact() {
+                    out.push_str(&self.format_day(weeks[0][first_day], options));
+                    continue;
+                }
+                let block: Vec<Vec<Option<u32>>> = weeks.iter()
+                    .map(|week| {
+                        (first_day..first_day + days_per_glyph)
+                            .map(|day_idx| week.get(day_idx).map(|date| self.count(*date)))
+                            .collect()
+                    })
+                    .collect();
+                out.push_str(&self.format_glyph(&block, options));
+            }
+
+            if options.week_numbers {
+                if let Some(week) = Self::iso_week(&weeks[0]) {
+                    let _ = write!(out, " {:>2}", week);
+                }
+            }
+            out.push('\n');
+        }
+
+        Self::write_border(out, width, "");
+    }
+
+    // Write weekday names above the columns, if they fit in a glyph
+    fn write_weekday_header(&self, out: &mut String, options: &RenderOptions) {
+        let mode = options.mode;
+        let mut line = " ".repeat(GUTTER_WIDTH);
+
+        if mode.weeks_per_glyph() == 1 {
+            let labels: Vec<String> = (0..7)
+                .map(|day_idx| {
+                    let name = options.locale.weekday(self.weekday_of_row(day_idx));
+                    Self::truncate(name, mode.glyph_width())
+                })
+                .collect();
+
+            // Skip the header when a label does not fit in its column
+            if labels.iter().all(|label| !label.is_empty()) {
+                for label in labels {
+                    line.push_str(&locale::pad(&label, mode.glyph_width()));
+                }
+            }
+        }
+
+        let _ = writeln!(out, "{}", line.trim_end());
+    }
+
+    // Cut a label down to a display width
+    fn truncate(label: &str, width: usize) -> String {
+        let mut truncated = String::new();
+        for c in label.chars() {
+            let mut candidate = truncated.clone();
+            candidate.push(c);
+            if locale::display_width(&candidate) > width {
+                break;
+            }
+            truncated = candidate;
+        }
+        truncated
+    }
+}
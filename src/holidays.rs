@@ -0,0 +1,85 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+// Bundled public holiday calendars, computed from their rules
+pub const CALENDARS: &[&str] = &["us", "gb", "de"];
+
+// Holidays of a bundled calendar in a year, or None for an unknown calendar
+pub fn bundled(calendar: &str, year: i32) -> Option<Vec<(NaiveDate, &'static str)>> {
+    let date = |month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day);
+    let easter = easter_sunday(year);
+    let after_easter = |days: i64| easter.map(|easter| easter + Duration::days(days));
+
+    let holidays = match calendar.to_lowercase().as_str() {
+        "us" => vec![
+            (date(1, 1), "New Year's Day"),
+            (nth_weekday(year, 1, Weekday::Mon, 3), "Martin Luther King Jr. Day"),
+            (nth_weekday(year, 2, Weekday::Mon, 3), "Presidents' Day"),
+            (last_weekday(year, 5, Weekday::Mon), "Memorial Day"),
+            (date(6, 19), "Juneteenth"),
+            (date(7, 4), "Independence Day"),
+            (nth_weekday(year, 9, Weekday::Mon, 1), "Labor Day"),
+            (nth_weekday(year, 10, Weekday::Mon, 2), "Columbus Day"),
+            (date(11, 11), "Veterans Day"),
+            (nth_weekday(year, 11, Weekday::Thu, 4), "Thanksgiving Day"),
+            (date(12, 25), "Christmas Day"),
+        ],
+        "gb" | "uk" => vec![
+            (date(1, 1), "New Year's Day"),
+            (after_easter(-2), "Good Friday"),
+            (after_easter(1), "Easter Monday"),
+            (nth_weekday(year, 5, Weekday::Mon, 1), "Early May Bank Holiday"),
+            (last_weekday(year, 5, Weekday::Mon), "Spring Bank Holiday"),
+            (last_weekday(year, 8, Weekday::Mon), "Summer Bank Holiday"),
+            (date(12, 25), "Christmas Day"),
+            (date(12, 26), "Boxing Day"),
+        ],
+        "de" => vec![
+            (date(1, 1), "Neujahr"),
+            (after_easter(-2), "Karfreitag"),
+            (after_easter(1), "Ostermontag"),
+            (date(5, 1), "Tag der Arbeit"),
+            (after_easter(39), "Christi Himmelfahrt"),
+            (after_easter(50), "Pfingstmontag"),
+            (date(10, 3), "Tag der Deutschen Einheit"),
+            (date(12, 25), "1. Weihnachtstag"),
+            (date(12, 26), "2. Weihnachtstag"),
+        ],
+        _ => return None,
+    };
+
+    Some(holidays.into_iter().filter_map(|(date, name)| date.map(|date| (date, name))).collect())
+}
+
+// Easter Sunday in the Gregorian calendar (anonymous Gregorian algorithm)
+fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+// The nth weekday of a month, e.g. the fourth Thursday of November
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n)
+}
+
+// The last weekday of a month, e.g. the last Monday of May
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> Option<NaiveDate> {
+    let mut date = NaiveDate::from_ymd_opt(year, month, 1)?.checked_add_months(chrono::Months::new(1))?.pred_opt()?;
+    while date.weekday() != weekday {
+        date = date.pred_opt()?;
+    }
+    Some(date)
+}
@@ -0,0 +1,24 @@
+// Terminal heatmaps of GitHub contributions; the gh-heat binary is built on these modules
+
+pub mod activity;
+pub mod cache;
+pub mod clock;
+pub mod compare;
+pub mod config;
+pub mod error;
+pub mod export;
+pub mod git;
+pub mod github;
+pub mod glyphs;
+pub mod heatmap;
+pub mod holidays;
+pub mod locale;
+pub mod overlay;
+pub mod punchcard;
+pub mod stats;
+pub mod term;
+pub mod theme;
+pub mod token;
+pub mod tui;
+pub mod watch;
+pub mod zone;
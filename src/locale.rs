@@ -0,0 +1,153 @@
+use crate::error::GhHeatError;
+use anyhow::Result;
+use chrono::Weekday;
+use unicode_width::UnicodeWidthStr;
+
+// Month, weekday and legend labels for one language
+#[derive(Debug)]
+pub struct Locale {
+    pub language: &'static str,
+    months: [&'static str; 12],
+    // Weekday abbreviations from Monday to Sunday
+    weekdays: [&'static str; 7],
+    pub less: &'static str,
+    pub more: &'static str,
+}
+
+const LOCALES: &[Locale] = &[
+    Locale {
+        language: "en",
+        months: ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"],
+        weekdays: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+        less: "Less",
+        more: "More",
+    },
+    Locale {
+        language: "de",
+        months: ["Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez"],
+        weekdays: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+        less: "Weniger",
+        more: "Mehr",
+    },
+    Locale {
+        language: "fr",
+        months: ["janv", "févr", "mars", "avr", "mai", "juin", "juil", "août", "sept", "oct", "nov", "déc"],
+        weekdays: ["lun", "mar", "mer", "jeu", "ven", "sam", "dim"],
+        less: "Moins",
+        more: "Plus",
+    },
+    Locale {
+        language: "es",
+        months: ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic"],
+        weekdays: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+        less: "Menos",
+        more: "Más",
+    },
+    Locale {
+        language: "it",
+        months: ["gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic"],
+        weekdays: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+        less: "Meno",
+        more: "Più",
+    },
+    Locale {
+        language: "pt",
+        months: ["jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov", "dez"],
+        weekdays: ["seg", "ter", "qua", "qui", "sex", "sáb", "dom"],
+        less: "Menos",
+        more: "Mais",
+    },
+    Locale {
+        language: "nl",
+        months: ["jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec"],
+        weekdays: ["ma", "di", "wo", "do", "vr", "za", "zo"],
+        less: "Minder",
+        more: "Meer",
+    },
+    Locale {
+        language: "pl",
+        months: ["sty", "lut", "mar", "kwi", "maj", "cze", "lip", "sie", "wrz", "paź", "lis", "gru"],
+        weekdays: ["pon", "wto", "śro", "czw", "pią", "sob", "nie"],
+        less: "Mniej",
+        more: "Więcej",
+    },
+    Locale {
+        language: "ru",
+        months: ["янв", "фев", "мар", "апр", "май", "июн", "июл", "авг", "сен", "окт", "ноя", "дек"],
+        weekdays: ["пн", "вт", "ср", "чт", "пт", "сб", "вс"],
+        less: "Меньше",
+        more: "Больше",
+    },
+    Locale {
+        language: "ja",
+        months: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+        weekdays: ["月", "火", "水", "木", "金", "土", "日"],
+        less: "少",
+        more: "多",
+    },
+    Locale {
+        language: "zh",
+        months: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+        weekdays: ["一", "二", "三", "四", "五", "六", "日"],
+        less: "少",
+        more: "多",
+    },
+    Locale {
+        language: "ko",
+        months: ["1월", "2월", "3월", "4월", "5월", "6월", "7월", "8월", "9월", "10월", "11월", "12월"],
+        weekdays: ["월", "화", "수", "목", "금", "토", "일"],
+        less: "적음",
+        more: "많음",
+    },
+];
+
+impl Locale {
+    // Find the locale for a name like "de", "ja_JP" or "pt_BR.UTF-8"
+    pub fn from_name(name: &str) -> Option<&'static Locale> {
+        let language = name.split(['_', '-', '.', '@']).next().unwrap_or("").to_lowercase();
+        match language.as_str() {
+            "c" | "posix" => Some(&LOCALES[0]),
+            _ => LOCALES.iter().find(|locale| locale.language == language),
+        }
+    }
+
+    // Pick the locale from --locale or the LC_ALL, LC_TIME and LANG variables
+    pub fn select(requested: Option<&str>) -> Result<&'static Locale> {
+        if let Some(name) = requested {
+            let available: Vec<&str> = LOCALES.iter().map(|locale| locale.language).collect();
+            return Self::from_name(name).ok_or_else(|| {
+                GhHeatError::Config(format!(
+                    "unsupported locale '{}' (available: {})",
+                    name,
+                    available.join(", ")
+                )).into()
+            });
+        }
+
+        // The first non-empty variable decides, falling back to English
+        let env_locale = ["LC_ALL", "LC_TIME", "LANG"].iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty());
+        Ok(env_locale.as_deref().and_then(Self::from_name).unwrap_or(&LOCALES[0]))
+    }
+
+    // Abbreviated month name from month number
+    pub fn month(&self, month: u32) -> &'static str {
+        self.months.get(month as usize - 1).copied().unwrap_or("")
+    }
+
+    // Abbreviated weekday name
+    pub fn weekday(&self, weekday: Weekday) -> &'static str {
+        self.weekdays[weekday.num_days_from_monday() as usize]
+    }
+}
+
+// Width of a label in terminal columns, counting CJK characters as two
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+// Pad a label with spaces to a display width
+pub fn pad(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(display_width(text))))
+}
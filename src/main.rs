@@ -1,69 +1,636 @@
 use anyhow::Result;
-use clap::Parser;
-use colored::Colorize;
+use chrono::{Duration, NaiveDate};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-mod error;
-mod github;
-mod heatmap;
-
-use github::GithubClient;
-use heatmap::Heatmap;
+use gh_heat::{activity, error, export, git, overlay, stats, term, theme, token, tui, watch, zone};
+use gh_heat::cache::Cache;
+use gh_heat::clock::Clock;
+use gh_heat::compare::{Comparison, Side};
+use gh_heat::config::{Config, Settings};
+use gh_heat::error::GhHeatError;
+use gh_heat::github::{Contributions, Endpoints, GithubClient, Source};
+use gh_heat::heatmap::{Fit, Heatmap, Orientation, RenderMode, RenderOptions, WeekStart};
+use gh_heat::locale::Locale;
+use gh_heat::overlay::Overlays;
+use gh_heat::punchcard::{Punchcard, PunchcardSource};
+use gh_heat::stats::Report;
+use gh_heat::term::{ColorChoice, ColorSupport};
+use gh_heat::theme::Theme;
+use gh_heat::token::Token;
+use gh_heat::watch::Watch;
+use gh_heat::zone::Zone;
 
 /// GitHub Contribution Heatmap Generator
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
+#[clap(subcommand_negates_reqs = true, override_usage = "gh-heat [OPTIONS] <USERNAME>\n       gh-heat <COMMAND> [OPTIONS]")]
 struct Args {
-    /// GitHub username to generate heatmap for
-    #[clap(index = 1)]
-    username: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    // `gh-heat <user>` is short for `gh-heat show <user>`
+    #[command(flatten)]
+    show: ShowArgs,
 
     /// Number of days to include in the heatmap (default: 365)
-    #[clap(short, long, default_value = "365")]
+    #[clap(short, long, default_value = "365", global = true)]
     days: u32,
 
-    /// Use a dark color scheme (red gradient)
-    #[clap(short, long)]
+    /// Color theme: github, github-dark, halloween, ice, grayscale, viridis, cividis
+    /// or a custom theme from the config file
+    #[clap(long, default_value = theme::DEFAULT_THEME, global = true)]
+    theme: String,
+
+    /// Shorthand for --theme github-dark
+    #[clap(long, hide = true, conflicts_with = "theme", global = true)]
     dark_mode: bool,
 
-    /// Use symbols instead of colors
-    #[clap(short, long)]
+    /// How days are drawn: blocks, symbols, numbers, or the compact half, quadrant and braille glyphs
+    #[clap(short, long, value_enum, default_value = "blocks", global = true)]
+    mode: RenderMode,
+
+    /// Use symbols instead of colors (same as --mode symbols)
+    #[clap(short, long, conflicts_with = "mode", global = true)]
     symbols: bool,
 
-    /// Show numbers instead of colors or symbols
-    #[clap(short = 'n', long)]
+    /// Show numbers instead of colors or symbols (same as --mode numbers)
+    #[clap(short = 'n', long, conflicts_with_all = ["mode", "symbols"], global = true)]
     numbers: bool,
 
-    /// Show total contribution counts
+    /// First day of the week
+    #[clap(long, value_enum, default_value = "sunday", global = true)]
+    week_start: WeekStart,
+
+    /// Show ISO week numbers above the grid
+    #[clap(long, global = true)]
+    week_numbers: bool,
+
+    /// Language for month and weekday labels, e.g. de or ja_JP (default: from LC_ALL, LC_TIME or LANG)
+    #[clap(long, global = true)]
+    locale: Option<String>,
+
+    /// What to do when the heatmap is wider than the terminal
+    #[clap(long, value_enum, default_value = "wrap", global = true)]
+    fit: Fit,
+
+    /// Terminal width in columns (detected automatically by default)
+    #[clap(long, global = true)]
+    width: Option<usize>,
+
+    /// When to use colors: auto, always or never
+    #[clap(long, value_enum, default_value = "auto", global = true)]
+    color: ColorChoice,
+
+    /// Mark public holidays from a bundled calendar (us, gb, de) or an .ics or .csv file
+    #[clap(long, value_name = "CALENDAR", global = true)]
+    holidays: Option<String>,
+
+    /// Mark custom dates or ranges from an .ics file or a CSV of "date,label" or "start..end,label"
+    #[clap(long, value_name = "FILE", global = true)]
+    markers: Option<PathBuf>,
+
+    /// Draw weekends in muted colors
+    #[clap(long, global = true)]
+    dim_weekends: bool,
+
+    /// Don't mark today in the grid
+    #[clap(long, global = true)]
+    no_today: bool,
+
+    /// Time zone that days start and end in: local, a name like America/Los_Angeles, or an offset like -08:00
+    #[clap(long, value_name = "ZONE", default_value = "local", value_parser = zone::parse, global = true)]
+    tz: Zone,
+
+    /// Settings from a [profile.NAME] table of the config file, over its [defaults]
+    #[clap(long, value_name = "NAME", global = true)]
+    profile: Option<String>,
+
+    /// GitHub Enterprise Server host to use instead of github.com, e.g. github.example.com
+    #[clap(long, global = true)]
+    host: Option<String>,
+
+    /// Read the GitHub token from this file instead of GH_TOKEN, GITHUB_TOKEN, gh or git credentials
+    #[clap(long, value_name = "FILE", global = true)]
+    token_file: Option<PathBuf>,
+
+    /// Print where the token came from and other diagnostics to stderr
+    #[clap(short, long, global = true)]
+    verbose: bool,
+
+    /// Where contribution counts come from; graphql fails instead of falling back
+    #[clap(long, value_enum, default_value = "auto", global = true)]
+    source: Source,
+
+    /// Wait and retry up to this many times after server errors and secondary rate limits
+    #[clap(long, value_name = "ATTEMPTS", default_value = "0", global = true)]
+    retries: u32,
+
+    /// Add contributions to private repositories the token can see to the daily counts,
+    /// and show private totals with the statistics (GraphQL only)
+    #[clap(long, global = true)]
+    private: bool,
+}
+
+// Options of the heatmap view, taken by `show` and by `gh-heat <user>`
+#[derive(clap::Args, Debug, Clone)]
+struct ShowArgs {
+    /// GitHub username to generate heatmap for
+    #[clap(required = true)]
+    username: Option<String>,
+
+    /// Layout direction: horizontal (weeks as columns) or vertical (weeks as rows)
+    #[clap(long, value_enum, default_value = "horizontal")]
+    orientation: Orientation,
+
+    /// Show contribution statistics and charts above the heatmap
     #[clap(short, long)]
     totals: bool,
+
+    /// Show only the statistics, without the heatmap (same as the stats command)
+    #[clap(long, hide = true)]
+    stats_only: bool,
+
+    /// Also show streaks that skip weekends with the totals
+    #[clap(long)]
+    weekday_streaks: bool,
+
+    /// Show a weekday x hour punchcard of commit times instead of the heatmap,
+    /// from auto, graphql, events or git
+    #[clap(long, value_enum, num_args = 0..=1, require_equals = true, default_missing_value = "auto")]
+    punchcard: Option<PunchcardSource>,
+
+    /// Local repository for --punchcard=git
+    #[clap(long, default_value = ".")]
+    repo: PathBuf,
+
+    /// Only count local commits whose author matches this pattern (--punchcard=git)
+    #[clap(long)]
+    author: Option<String>,
+
+    /// Print contributions and statistics as JSON instead of the heatmap (same as the export command)
+    #[clap(long, hide = true)]
+    json: bool,
+
+    /// Keep the heatmap open and refetch the current week every interval, e.g. 60, 30s or 5m
+    #[clap(long, value_name = "INTERVAL", value_parser = watch::parse_interval, conflicts_with_all = ["json", "stats_only", "punchcard"])]
+    watch: Option<std::time::Duration>,
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
-    let client = GithubClient::new()?;
-    let contributions = client.get_user_contributions(&args.username, args.days)?;
-    
-    let heatmap = Heatmap::new(contributions);
-    
-    if args.totals {
-        let total_commits = heatmap.total_contributions();
-        let active_days = heatmap.active_days();
-        let max_contributions = heatmap.max_contributions_in_day();
-        let average = if active_days > 0 {
-            total_commits as f64 / active_days as f64
-        } else {
-            0.0
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Show the contribution heatmap (the default when no command is given)
+    Show(ShowArgs),
+    /// Show contribution statistics and charts without the heatmap
+    Stats {
+        /// GitHub username to summarize
+        username: String,
+
+        /// Also show streaks that skip weekends
+        #[clap(long)]
+        weekday_streaks: bool,
+    },
+    /// Compare two users, or two years, side by side with a difference heatmap
+    Compare {
+        /// GitHub username for the first heatmap
+        username: String,
+
+        /// GitHub username for the second heatmap (default: the same user)
+        #[clap(required_unless_present = "years")]
+        other: Option<String>,
+
+        /// Compare two calendar years instead of the last --days days
+        #[clap(long, num_args = 2, value_names = ["YEAR", "OTHER_YEAR"])]
+        years: Option<Vec<i32>>,
+    },
+    /// List the commits, pull requests, reviews and issues of one day (requires a token)
+    Day {
+        /// GitHub username to look up
+        username: String,
+
+        /// Day to list, as YYYY-MM-DD
+        date: NaiveDate,
+    },
+    /// Browse the heatmap interactively with a cursor and day details
+    Tui {
+        /// GitHub username to browse
+        username: String,
+    },
+    /// Export contributions and statistics as JSON
+    Export {
+        /// GitHub username to export
+        username: String,
+
+        /// Write to this file instead of standard output
+        #[clap(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Show where contributions are cached, or delete the cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Show where the config file is, or the settings in effect
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Print a shell completion script, e.g. `gh-heat completions zsh > _gh-heat`
+    Completions {
+        /// Shell to generate completions for
+        #[clap(value_enum)]
+        shell: Shell,
+    },
+    /// Print the man page in roff format, e.g. `gh-heat man > gh-heat.1`
+    Man,
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheAction {
+    /// Print the cache directory
+    Path,
+    /// Delete the cached contributions of a user, or of everyone
+    Clear {
+        /// Only clear this user's cache
+        username: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    /// Print the location of the config file
+    Path,
+    /// Print the settings in effect after the config file, --profile and command-line flags
+    Show,
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {:?}", error);
+            ExitCode::from(error::exit_code(&error))
+        }
+    }
+}
+
+impl Args {
+    // The show options only make sense for the heatmap, so they can't go with another command.
+    // Clap can't check this itself while global options are also accepted before a command
+    fn check_show_args(matches: &ArgMatches) {
+        let Some((name, _)) = matches.subcommand() else {
+            return;
         };
+        let show = <ShowArgs as clap::Args>::augment_args(clap::Command::new("show"));
+        let given = show.get_arguments()
+            .find(|arg| matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine));
+        if let Some(given) = given {
+            let mut command = Args::command();
+            command.build();
+            let arg = command.get_arguments().find(|arg| arg.get_id() == given.get_id()).unwrap_or(given);
+            let message = format!("the subcommand '{}' cannot be used with '{}'", name, arg);
+            command.error(ErrorKind::ArgumentConflict, message).exit();
+        }
+    }
+    
+    // Fill in options not given on the command line from the config file
+    fn apply(&mut self, settings: Settings, matches: &ArgMatches) {
+        let from_cli = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+        if let (false, Some(theme)) = (from_cli("theme") || self.dark_mode, settings.theme) {
+            self.theme = theme;
+        }
+        if let (false, Some(days)) = (from_cli("days"), settings.days) {
+            self.days = days;
+        }
+        if let (false, Some(week_start)) = (from_cli("week_start"), settings.week_start) {
+            self.week_start = week_start;
+        }
+        if let (false, Some(source)) = (from_cli("source"), settings.source) {
+            self.source = source;
+        }
+        self.host = self.host.take().or(settings.host);
+        self.token_file = self.token_file.take().or(settings.token_file);
+    }
+}
 
-        println!("\nUser: {}", args.username.bright_white().bold());
-        println!("Total Contributions: {}", total_commits.to_string().green());
-        println!("Active Days: {}", active_days.to_string().green());
-        println!("Max Contributions in a Day: {}", max_contributions.to_string().green());
-        println!("Average Contributions on Active Days: {:.2}\n", average);
+fn run() -> Result<()> {
+    let matches = Args::command().get_matches();
+    Args::check_show_args(&matches);
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+    let config = Config::load()?;
+    args.apply(config.settings(args.profile.as_deref())?, &matches);
+    let command = args.command.take().unwrap_or_else(|| Command::Show(args.show.clone()));
+    
+    // Commands that need neither the terminal nor GitHub
+    match &command {
+        Command::Completions { shell } => {
+            // Generated into a buffer, since clap_complete panics when stdout is closed early
+            let mut script = Vec::new();
+            clap_complete::generate(*shell, &mut Args::command(), env!("CARGO_BIN_NAME"), &mut script);
+            std::io::stdout().write_all(&script)?;
+            return Ok(());
+        }
+        Command::Man => {
+            clap_mangen::Man::new(Args::command()).render(&mut std::io::stdout())?;
+            return Ok(());
+        }
+        Command::Cache { action } => return cache_command(action),
+        Command::Config { action } => return config_command(action, &args, &config),
+        _ => {}
     }
     
-    // Render the heatmap
-    heatmap.render(args.dark_mode, args.symbols, args.numbers);
+    let orientation = match &command {
+        Command::Show(show) => show.orientation,
+        _ => Orientation::Horizontal,
+    };
+    let theme_name = if args.dark_mode { "github-dark" } else { &args.theme };
+    let locale = Locale::select(args.locale.as_deref())?;
+    let colors = ColorSupport::detect(args.color);
+    let theme = Theme::load(theme_name, &config)?.for_colors(colors);
+    colored::control::set_override(colors != ColorSupport::None);
+    
+    let mut mode = if args.numbers {
+        RenderMode::Numbers
+    } else if args.symbols {
+        RenderMode::Symbols
+    } else {
+        args.mode
+    };
+    
+    // Colored blocks are invisible without colors, so fall back to symbols
+    if mode == RenderMode::Blocks && colors == ColorSupport::None {
+        mode = RenderMode::Symbols;
+    }
+    
+    let max_width = match (args.fit, orientation) {
+        (Fit::None, _) | (_, Orientation::Vertical) => None,
+        _ => args.width.or_else(term::terminal_width),
+    };
+    // Context drawn over the grid
+    let clock = Clock::system(args.tz);
+    let today = clock.today();
+    let overlays = Overlays {
+        today: (!args.no_today).then_some(today),
+        dim_weekends: args.dim_weekends,
+        holidays: match &args.holidays {
+            Some(calendar) => overlay::load_holidays(calendar, today)?,
+            None => Default::default(),
+        },
+        markers: match &args.markers {
+            Some(path) => overlay::load_calendar(path)?,
+            None => Default::default(),
+        },
+    };
+    
+    let mut options = RenderOptions {
+        theme: &theme,
+        colors,
+        mode,
+        max_width,
+        week_numbers: args.week_numbers,
+        locale,
+        orientation,
+        cursor: None,
+        highlights: &[],
+        overlays: &overlays,
+    };
+    
+    let host = args.host.clone().unwrap_or_else(|| token::GITHUB_HOST.to_string());
+    let verbose = args.verbose;
+    let report = move |token: Option<Token>| {
+        if verbose {
+            match &token {
+                Some(token) => eprintln!("Using a token from {}", token.source),
+                None => eprintln!("No token found; using public data only"),
+            }
+        }
+        token.map(|token| token.value)
+    };
+    let client = GithubClient::new(clock)?.with_endpoints(Endpoints::for_host(&host));
+    // A --token-file is read up front so a bad one fails right away. The other lookups may start
+    // gh or git, so they wait until a request needs a token
+    let client = match &args.token_file {
+        Some(path) => client.with_token(report(token::discover(&host, Some(path))?)),
+        None => client.with_token_lookup(move || report(token::discover(&host, None).unwrap_or_default())),
+    };
+    let client = client
+        .with_retries(args.retries)
+        .with_source(args.source)
+        .with_private(args.private);
+    
+    if let Command::Compare { username, other, years } = &command {
+        let other = other.as_deref().unwrap_or(username);
+        
+        // Each side covers a calendar year up to today, or the same last --days days
+        let period = |year: Option<i32>| match year {
+            Some(year) => (
+                NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or(today),
+                NaiveDate::from_ymd_opt(year, 12, 31).unwrap_or(today).min(today),
+            ),
+            None => (today - Duration::days(args.days as i64), today),
+        };
+        let side = |username: &str, year: Option<i32>| -> Result<Side> {
+            let (start_date, end_date) = period(year);
+            let label = match year {
+                Some(year) => format!("{} {}", username, year),
+                None => username.to_string(),
+            };
+            Ok(Side {
+                label,
+                contributions: client.get_user_contributions_between(username, start_date, end_date)?.counts,
+                start_date,
+                end_date,
+            })
+        };
+        let years = years.as_deref().unwrap_or_default();
+        
+        let comparison = Comparison::new(
+            side(username, years.first().copied())?,
+            side(other, years.get(1).copied())?,
+            args.week_start,
+        );
+        if let (Fit::Compact, Some(max_width)) = (args.fit, max_width) {
+            options.mode = comparison.fitting_mode(mode, max_width);
+        }
+        warn_compact_overlays(&options);
+        print!("{}", comparison.render_to_string(&options));
+        return Ok(());
+    }
+    
+    if let Command::Day { username, date } = &command {
+        let activities = client.get_day_activity(username, *date)?;
+        print!("{}", activity::day_report(username, *date, &activities, locale));
+        return Ok(());
+    }
+    
+    if let Command::Tui { username } = &command {
+        let contributions = client.get_user_contributions(username, args.days)?;
+        let heatmap = Heatmap::new(contributions.counts, args.week_start, today);
+        let start_date = today - Duration::days(args.days as i64);
+        
+        // Details are optional, so the view still opens when they cannot be fetched
+        let activities = client.get_activity(username, today - Duration::days(args.days.min(365) as i64), today)
+            .unwrap_or_else(|err| {
+                eprintln!("Warning: could not fetch activity details: {}", err);
+                Vec::new()
+            });
+        
+        let mut app = tui::App::new(username, heatmap, &activities, (start_date, today), theme_name, &config, options)?;
+        return app.run();
+    }
+    
+    if let Command::Stats { username, weekday_streaks } = &command {
+        let contributions = client.get_user_contributions(username, args.days)?;
+        let heatmap = Heatmap::new(contributions.counts.clone(), args.week_start, today);
+        print_report(username, &heatmap, &contributions, &options, *weekday_streaks, args.private);
+        print_source(args.source, &contributions);
+        return Ok(());
+    }
+    
+    if let Command::Export { username, output } = &command {
+        let contributions = client.get_user_contributions(username, args.days)?;
+        let heatmap = Heatmap::new(contributions.counts, args.week_start, today);
+        let json = export::to_json(username, &heatmap, contributions.source, contributions.private)?;
+        match output {
+            Some(path) => std::fs::write(path, json + "\n")
+                .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?,
+            None => println!("{}", json),
+        }
+        return Ok(());
+    }
+    
+    let Command::Show(show) = command else {
+        unreachable!("every other command returns above");
+    };
+    // Clap requires a username for show
+    let username = show.username.as_deref().unwrap_or_default();
+    
+    if let Some(source) = show.punchcard {
+        let timestamps = match source {
+            PunchcardSource::Git => git::commit_timestamps(&show.repo, show.author.as_deref(), args.days)?,
+            PunchcardSource::Graphql => client.get_commit_timestamps(username, args.days)?,
+            PunchcardSource::Events => client.get_event_timestamps(username)?,
+            PunchcardSource::Auto if client.has_token() => client.get_commit_timestamps(username, args.days)?,
+            PunchcardSource::Auto => client.get_event_timestamps(username)?,
+        };
+        let punchcard = Punchcard::new(&timestamps, args.tz);
+        print!("{}", punchcard.render_to_string(&options, args.week_start.weekday()));
+        return Ok(());
+    }
+    
+    if let Some(interval) = show.watch {
+        // The client's notes would land in the middle of the redrawn screen
+        let client = client.with_quiet(true);
+        let watch = Watch {
+            client: &client,
+            username,
+            days: args.days,
+            week_start: args.week_start,
+            interval,
+            clock,
+            width: args.width,
+        };
+        return watch.run(options, args.fit);
+    }
+    
+    let contributions = client.get_user_contributions(username, args.days)?;
+    
+    let heatmap = Heatmap::new(contributions.counts.clone(), args.week_start, today);
+    
+    if show.json {
+        println!("{}", export::to_json(username, &heatmap, contributions.source, contributions.private)?);
+        return Ok(());
+    }
+    
+    if let (Fit::Compact, Some(max_width)) = (args.fit, max_width) {
+        options.mode = heatmap.fitting_mode(mode, max_width);
+    }
+    if !show.stats_only {
+        warn_compact_overlays(&options);
+    }
+    
+    if show.totals || show.stats_only {
+        print_report(username, &heatmap, &contributions, &options, show.weekday_streaks, args.private);
+    }
+    if !show.stats_only {
+        heatmap.render(&options);
+    }
+    print_source(args.source, &contributions);
+    
+    Ok(())
+}
+
+// Statistics and charts, followed by private totals when --private asked for them
+fn print_report(username: &str, heatmap: &Heatmap, contributions: &Contributions, options: &RenderOptions, weekday_streaks: bool, private: bool) {
+    let report = Report::new(heatmap);
+    print!("{}", report.to_text(username, options, weekday_streaks));
+    if private {
+        match &contributions.private {
+            Some(private) => print!("{}", stats::private_to_text(private)),
+            None => eprintln!("Note: private contributions are only reported by GraphQL, which needs a token"),
+        }
+    }
+}
+
+// Compact glyphs pack several days into a character, so holidays and markers can't be drawn
+fn warn_compact_overlays(options: &RenderOptions) {
+    if options.mode.is_compact() && options.overlays.has_marks() {
+        let mode = options.mode.to_possible_value().map(|value| value.get_name().to_string()).unwrap_or_default();
+        eprintln!("Note: --holidays and --markers are not shown in the {} mode", mode);
+    }
+}
+
+// Auto mode may have fallen back, so say which source the counts came from
+fn print_source(source: Source, contributions: &Contributions) {
+    if source == Source::Auto {
+        println!("Source: {}", contributions.source.describe());
+    }
+}
+
+fn cache_command(action: &CacheAction) -> Result<()> {
+    match action {
+        CacheAction::Path => {
+            let dir = Cache::dir().ok_or_else(|| GhHeatError::Cache("no cache directory: set XDG_CACHE_HOME or HOME".to_string()))?;
+            println!("{}", dir.display());
+        }
+        CacheAction::Clear { username } => {
+            let removed = Cache::clear(username.as_deref())?;
+            eprintln!("Removed {} cache file{}", removed, if removed == 1 { "" } else { "s" });
+        }
+    }
+    
+    Ok(())
+}
+
+fn config_command(action: &ConfigAction, args: &Args, config: &Config) -> Result<()> {
+    let path = Config::path().ok_or_else(|| GhHeatError::Config("no config directory: set XDG_CONFIG_HOME or HOME".to_string()))?;
+    match action {
+        ConfigAction::Path => println!("{}", path.display()),
+        ConfigAction::Show => {
+            let value_name = |value: Option<clap::builder::PossibleValue>| value.map(|value| value.get_name().to_string()).unwrap_or_default();
+            let mut profiles: Vec<&str> = config.profile.keys().map(String::as_str).collect();
+            profiles.sort_unstable();
+            
+            println!("# {}{}", path.display(), if path.exists() { "" } else { " (not found)" });
+            println!("# profiles: {}", if profiles.is_empty() { "none".to_string() } else { profiles.join(", ") });
+            if let Some(profile) = &args.profile {
+                println!("# using profile {}", profile);
+            }
+            println!("theme = {:?}", if args.dark_mode { "github-dark" } else { &args.theme });
+            println!("days = {}", args.days);
+            println!("week_start = {:?}", value_name(args.week_start.to_possible_value()));
+            println!("source = {:?}", value_name(args.source.to_possible_value()));
+            println!("host = {:?}", args.host.as_deref().unwrap_or(token::GITHUB_HOST));
+            if let Some(token_file) = &args.token_file {
+                println!("token_file = {:?}", token_file.display().to_string());
+            }
+        }
+    }
     
     Ok(())
 }
@@ -0,0 +1,175 @@
+use crate::error::GhHeatError;
+use crate::holidays;
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// Years before and after today covered by bundled holiday calendars
+const BUNDLED_YEARS: i32 = 20;
+
+// Context drawn over the grid: today, dimmed weekends, holidays and custom markers
+#[derive(Debug, Default)]
+pub struct Overlays {
+    pub today: Option<NaiveDate>,
+    pub dim_weekends: bool,
+    pub holidays: BTreeMap<NaiveDate, String>,
+    pub markers: BTreeMap<NaiveDate, String>,
+}
+
+impl Overlays {
+    // Whether any holidays or custom markers are loaded
+    pub fn has_marks(&self) -> bool {
+        !self.holidays.is_empty() || !self.markers.is_empty()
+    }
+}
+
+// A run of consecutive days with the same label
+pub struct LabeledSpan<'a> {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub label: &'a str,
+}
+
+// Load holidays from a bundled calendar like "us" or from an ICS or CSV file
+pub fn load_holidays(calendar: &str, today: NaiveDate) -> Result<BTreeMap<NaiveDate, String>> {
+    if holidays::bundled(calendar, today.year()).is_some() {
+        let mut days = BTreeMap::new();
+        for year in today.year() - BUNDLED_YEARS..=today.year() + BUNDLED_YEARS {
+            for (date, name) in holidays::bundled(calendar, year).unwrap_or_default() {
+                days.insert(date, name.to_string());
+            }
+        }
+        return Ok(days);
+    }
+
+    let path = Path::new(calendar);
+    if !path.exists() {
+        return Err(GhHeatError::Config(format!(
+            "unknown holiday calendar '{}' (bundled: {}, or a path to an .ics or .csv file)",
+            calendar,
+            holidays::CALENDARS.join(", ")
+        )).into());
+    }
+    load_calendar(path)
+}
+
+// Load labeled days from an ICS file, or from CSV lines of "date,label" or "start..end,label"
+pub fn load_calendar(path: &Path) -> Result<BTreeMap<NaiveDate, String>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| GhHeatError::Config(format!("{}: {}", path.display(), e)))?;
+    let is_ics = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("ics"));
+
+    let days = if is_ics { parse_ics(&contents) } else { parse_csv(&contents) };
+    days.map_err(|e| GhHeatError::Config(format!("{}: {}", path.display(), e)).into())
+}
+
+// Labeled days between two dates, with consecutive days of the same label merged
+pub fn spans(days: &BTreeMap<NaiveDate, String>, start_date: NaiveDate, end_date: NaiveDate) -> Vec<LabeledSpan<'_>> {
+    let mut spans: Vec<LabeledSpan> = Vec::new();
+    for (date, label) in days.range(start_date..=end_date) {
+        match spans.last_mut() {
+            Some(span) if span.label == label && span.end.succ_opt() == Some(*date) => span.end = *date,
+            _ => spans.push(LabeledSpan { start: *date, end: *date, label }),
+        }
+    }
+    spans
+}
+
+// Parse CSV lines, skipping blank lines, # comments and a header row
+fn parse_csv(contents: &str) -> Result<BTreeMap<NaiveDate, String>, String> {
+    let mut days = BTreeMap::new();
+    let mut first_row = true;
+    for (line_idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let header_allowed = std::mem::replace(&mut first_row, false);
+
+        let (dates, label) = line.split_once(',').unwrap_or((line, ""));
+        let label = label.trim().trim_matches('"');
+        let parse = |text: &str| {
+            NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d")
+                .map_err(|_| format!("line {}: invalid date '{}'", line_idx + 1, text.trim()))
+        };
+        let (start, end) = match dates.split_once("..") {
+            Some((start, end)) => (parse(start), parse(end)),
+            None => (parse(dates), parse(dates)),
+        };
+        let (start, end) = match (start, end) {
+            (Ok(start), Ok(end)) => (start, end),
+            // A first line without a date is a header
+            _ if header_allowed => continue,
+            (Err(e), _) | (_, Err(e)) => return Err(e),
+        };
+
+        for date in start.iter_days().take_while(|date| *date <= end) {
+            days.insert(date, label.to_string());
+        }
+    }
+    Ok(days)
+}
+
+// Start, end and summary of an iCalendar event, with dates marked as whole days or not
+#[derive(Default)]
+struct IcsEvent {
+    start: Option<(NaiveDate, bool)>,
+    end: Option<(NaiveDate, bool)>,
+    summary: String,
+}
+
+// Parse the events of an iCalendar file; recurrence rules are not expanded
+fn parse_ics(contents: &str) -> Result<BTreeMap<NaiveDate, String>, String> {
+    // Continuation lines start with a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(previous)) => previous.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut days = BTreeMap::new();
+    let mut event: Option<IcsEvent> = None;
+    for line in &lines {
+        let (name, value) = match line.split_once(':') {
+            Some((name, value)) => (name, value.trim()),
+            None => continue,
+        };
+        // Property parameters like ";VALUE=DATE" follow the name
+        let property = name.split(';').next().unwrap_or(name).to_uppercase();
+
+        match (property.as_str(), event.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => event = Some(IcsEvent::default()),
+            ("DTSTART", Some(event)) => event.start = Some(parse_ics_date(value)?),
+            ("DTEND", Some(event)) => event.end = Some(parse_ics_date(value)?),
+            ("SUMMARY", Some(event)) => event.summary = value.replace("\\,", ",").replace("\\;", ";"),
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                if let Some(IcsEvent { start: Some((start, _)), end, summary }) = event.take() {
+                    // All-day events end on the day after their last day
+                    let end = match end {
+                        Some((end, true)) if end > start => end - Duration::days(1),
+                        Some((end, _)) if end >= start => end,
+                        _ => start,
+                    };
+                    for date in start.iter_days().take_while(|date| *date <= end) {
+                        days.insert(date, summary.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(days)
+}
+
+// Parse an iCalendar date or date-time, returning whether it was a whole day
+fn parse_ics_date(value: &str) -> Result<(NaiveDate, bool), String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Ok((date, true));
+    }
+    NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S")
+        .map(|datetime| (datetime.date(), false))
+        .map_err(|_| format!("invalid date '{}'", value))
+}
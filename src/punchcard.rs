@@ -0,0 +1,114 @@
+use crate::heatmap::{Heatmap, RenderMode, RenderOptions};
+use crate::locale;
+use crate::zone::Zone;
+use chrono::{DateTime, Datelike, FixedOffset, Timelike, Weekday};
+use std::fmt::Write;
+
+// Width of the weekday label column
+const LABEL_WIDTH: usize = 4;
+
+// Working hours used for the after-hours share, as [start, end) on weekdays
+const WORK_HOURS: std::ops::Range<u32> = 9..18;
+
+// Where commit timestamps for the punchcard come from
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PunchcardSource {
+    /// GraphQL commit history with a token, otherwise the events API
+    Auto,
+    /// Commit history of each repository via GraphQL (requires a token)
+    Graphql,
+    /// Recent public events from the REST API (about the last 300 events)
+    Events,
+    /// Commits in a local git repository
+    Git,
+}
+
+// Activity counts by weekday and hour of day
+pub struct Punchcard {
+    // Indexed by weekday from Monday, then hour
+    counts: [[u32; 24]; 7],
+}
+
+impl Punchcard {
+    // Bucket timestamps by weekday and hour in a time zone
+    pub fn new(timestamps: &[DateTime<FixedOffset>], zone: Zone) -> Self {
+        let mut counts = [[0u32; 24]; 7];
+        for timestamp in timestamps {
+            let local = zone.convert(timestamp);
+            counts[local.weekday().num_days_from_monday() as usize][local.hour() as usize] += 1;
+        }
+
+        Self { counts }
+    }
+
+    // Total number of timestamps
+    pub fn total(&self) -> u32 {
+        self.counts.iter().flatten().sum()
+    }
+
+    // Share of activity outside weekday working hours, as a percentage
+    pub fn after_hours_percent(&self) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+
+        let working: u32 = self.counts[..5].iter()
+            .map(|hours| hours[WORK_HOURS.start as usize..WORK_HOURS.end as usize].iter().sum::<u32>())
+            .sum();
+        (total - working) as f64 / total as f64 * 100.0
+    }
+
+    // Render the punchcard with weekdays as rows and hours as columns
+    pub fn render_to_string(&self, options: &RenderOptions, week_start: Weekday) -> String {
+        let mut out = String::new();
+
+        // Compact glyphs pack several days together, so draw plain cells instead
+        let mode = match options.mode {
+            RenderMode::Numbers | RenderMode::Symbols => options.mode,
+            _ => RenderMode::Blocks,
+        };
+        let options = RenderOptions { mode, ..*options };
+        let cell_width = if mode == RenderMode::Numbers { 3 } else { 2 };
+        let max = self.counts.iter().flatten().copied().max().unwrap_or(0);
+
+        // Hour labels every three hours
+        let mut header = " ".repeat(LABEL_WIDTH);
+        for hour in (0..24).step_by(3) {
+            let column = LABEL_WIDTH + hour * cell_width;
+            header.push_str(&" ".repeat(column.saturating_sub(header.len())));
+            header.push_str(&hour.to_string());
+        }
+        let _ = writeln!(out, "\n{}", header);
+        let width = LABEL_WIDTH + 24 * cell_width;
+        let _ = writeln!(out, "{}", "=".repeat(width));
+
+        let mut weekday = week_start;
+        for _ in 0..7 {
+            out.push_str(&locale::pad(options.locale.weekday(weekday), LABEL_WIDTH));
+            for count in self.counts[weekday.num_days_from_monday() as usize] {
+                if mode == RenderMode::Numbers {
+                    let _ = write!(out, "{:3}", count);
+                } else {
+                    // Scale intensity relative to the busiest hour
+                    let intensity = if max == 0 { 0 } else { (count as usize * 5).div_ceil(max as usize) };
+                    out.push_str(&Heatmap::format_level(intensity, &options));
+                }
+            }
+            out.push('\n');
+            weekday = weekday.succ();
+        }
+
+        let _ = writeln!(out, "{}", "=".repeat(width));
+        let _ = writeln!(
+            out,
+            "\n  {} contributions, {:.0}% outside working hours (Mon-Fri {}:00-{}:00)",
+            self.total(),
+            self.after_hours_percent(),
+            WORK_HOURS.start,
+            WORK_HOURS.end
+        );
+
+        out
+    }
+}
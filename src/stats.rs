@@ -0,0 +1,301 @@
+use crate::github::PrivateTotals;
+use crate::heatmap::{Heatmap, RenderOptions, Span, Streaks};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+// Width of the longest bar in the charts
+const BAR_WIDTH: usize = 40;
+
+// Number of weeks per line of the weekly sparkline
+const SPARKLINE_WEEKS: usize = 52;
+
+// Eighth blocks for the partial end of a bar
+const BAR_EIGHTHS: [&str; 8] = ["", "\u{258F}", "\u{258E}", "\u{258D}", "\u{258C}", "\u{258B}", "\u{258A}", "\u{2589}"];
+
+// Sparkline glyphs from lowest to highest
+const SPARKS: [char; 8] = ['\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}'];
+
+// Total contributions in a day, week or month starting at a date
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Period {
+    pub start: NaiveDate,
+    pub total: u32,
+}
+
+// Total contributions on one day of the week
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct WeekdayTotal {
+    pub weekday: Weekday,
+    pub total: u32,
+}
+
+// Total contributions in a calendar year and the change from the same days of the year before,
+// when the range covers them
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct YearTotal {
+    pub year: i32,
+    pub total: u32,
+    pub change_percent: Option<f64>,
+}
+
+// Statistics computed from a heatmap
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub total_contributions: u32,
+    pub active_days: u32,
+    pub max_contributions_in_day: u32,
+    pub average_on_active_days: f64,
+    pub median_on_active_days: f64,
+    pub p90_on_active_days: u32,
+    pub streaks: Streaks,
+    pub weekday_streaks: Streaks,
+    pub busiest_day: Option<Period>,
+    pub busiest_week: Option<Period>,
+    pub busiest_month: Option<Period>,
+    // Totals by weekday, Monday first
+    pub weekday_totals: Vec<WeekdayTotal>,
+    pub weekly_totals: Vec<Period>,
+    pub monthly_totals: Vec<Period>,
+    pub yearly_totals: Vec<YearTotal>,
+}
+
+impl Report {
+    pub fn new(heatmap: &Heatmap) -> Self {
+        let mut days: Vec<(NaiveDate, u32)> = heatmap.contributions()
+            .iter()
+            .map(|(date, count)| (*date, *count))
+            .collect();
+        days.sort();
+
+        // Per-day distribution over active days
+        let mut active: Vec<u32> = days.iter().map(|(_, count)| *count).filter(|count| *count > 0).collect();
+        active.sort_unstable();
+        let median = match active.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (active[len / 2 - 1] + active[len / 2]) as f64 / 2.0,
+            len => active[len / 2] as f64,
+        };
+        let p90 = Self::percentile(&active, 90);
+
+        // Totals grouped by weekday, week, month and year
+        let mut weekday_totals = [0u32; 7];
+        let mut weeks = BTreeMap::new();
+        let mut months = BTreeMap::new();
+        let mut years = BTreeMap::new();
+        let week_start = heatmap.week_start();
+        for (date, count) in &days {
+            weekday_totals[date.weekday().num_days_from_monday() as usize] += count;
+
+            let offset = (date.weekday().num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7;
+            *weeks.entry(*date - Duration::days(offset as i64)).or_insert(0) += count;
+            *months.entry(date.with_day(1).unwrap_or(*date)).or_insert(0) += count;
+            *years.entry(date.year()).or_insert(0) += count;
+        }
+
+        let periods = |totals: BTreeMap<NaiveDate, u32>| -> Vec<Period> {
+            totals.into_iter().map(|(start, total)| Period { start, total }).collect()
+        };
+        let weekly_totals = periods(weeks);
+        let monthly_totals = periods(months);
+        let daily_totals: Vec<Period> = days.iter().map(|(start, total)| Period { start: *start, total: *total }).collect();
+
+        // A year is only compared with the same days of the year before, so a partial year
+        // at either end of the range doesn't show a made-up change
+        let covered = days.first().map(|(first, _)| (*first, heatmap.date_range().1));
+        let total_between = |start: NaiveDate, end: NaiveDate| -> u32 {
+            days.iter().filter(|(date, _)| *date >= start && *date <= end).map(|(_, count)| count).sum()
+        };
+        let yearly_totals = years.into_iter()
+            .map(|(year, total)| {
+                let change_percent = covered
+                    .and_then(|(first, last)| {
+                        let start = first.max(NaiveDate::from_ymd_opt(year, 1, 1)?);
+                        let end = last.min(NaiveDate::from_ymd_opt(year, 12, 31)?);
+                        let previous_start = same_day_year_before(start);
+                        (previous_start >= first).then(|| total_between(previous_start, same_day_year_before(end)))
+                    })
+                    .filter(|previous| *previous > 0)
+                    .map(|previous| (total as f64 - previous as f64) / previous as f64 * 100.0);
+                YearTotal { year, total, change_percent }
+            })
+            .collect();
+
+        Self {
+            total_contributions: heatmap.total_contributions(),
+            active_days: heatmap.active_days(),
+            max_contributions_in_day: heatmap.max_contributions_in_day(),
+            average_on_active_days: heatmap.average_on_active_days(),
+            median_on_active_days: median,
+            p90_on_active_days: p90,
+            streaks: heatmap.streaks(false),
+            weekday_streaks: heatmap.streaks(true),
+            busiest_day: Self::busiest(&daily_totals),
+            busiest_week: Self::busiest(&weekly_totals),
+            busiest_month: Self::busiest(&monthly_totals),
+            weekday_totals: (0..7)
+                .map(|idx| WeekdayTotal {
+                    weekday: Weekday::try_from(idx as u8).unwrap_or(Weekday::Mon),
+                    total: weekday_totals[idx],
+                })
+                .collect(),
+            weekly_totals,
+            monthly_totals,
+            yearly_totals,
+        }
+    }
+
+    // Nearest-rank percentile of sorted values
+    fn percentile(sorted: &[u32], percent: usize) -> u32 {
+        if sorted.is_empty() {
+            return 0;
+        }
+        let rank = (percent * sorted.len()).div_ceil(100).max(1);
+        sorted[rank - 1]
+    }
+
+    // Period with the most contributions, the earliest one on ties
+    fn busiest(periods: &[Period]) -> Option<Period> {
+        periods.iter()
+            .filter(|period| period.total > 0)
+            .fold(None, |best: Option<Period>, period| match best {
+                Some(best) if best.total >= period.total => Some(best),
+                _ => Some(*period),
+            })
+    }
+
+    // Format the statistics block with charts
+    pub fn to_text(&self, username: &str, options: &RenderOptions, weekday_streaks: bool) -> String {
+        let mut out = String::new();
+        let locale = options.locale;
+
+        let _ = writeln!(out, "\nUser: {}", username.bright_white().bold());
+        let _ = writeln!(out, "Total Contributions: {}", self.total_contributions.to_string().green());
+        let _ = writeln!(out, "Active Days: {}", self.active_days.to_string().green());
+        let _ = writeln!(out, "Max Contributions in a Day: {}", self.max_contributions_in_day.to_string().green());
+        let _ = writeln!(out, "Average Contributions on Active Days: {:.2}", self.average_on_active_days);
+        let _ = writeln!(out, "Median Contributions on Active Days: {:.1}", self.median_on_active_days);
+        let _ = writeln!(out, "90th Percentile on Active Days: {}", self.p90_on_active_days);
+        let _ = writeln!(out, "Current Streak: {}", describe_span(self.streaks.current).green());
+        let _ = writeln!(out, "Longest Streak: {}", describe_span(self.streaks.longest).green());
+        let _ = writeln!(out, "Longest Gap: {}", describe_span(self.streaks.longest_gap));
+        if weekday_streaks {
+            let _ = writeln!(out, "Current Weekday Streak: {}", describe_span(self.weekday_streaks.current).green());
+            let _ = writeln!(out, "Longest Weekday Streak: {}", describe_span(self.weekday_streaks.longest).green());
+        }
+
+        if let Some(day) = self.busiest_day {
+            let _ = writeln!(out, "Busiest Day: {} ({})", day.start.format("%Y-%m-%d"), day.total);
+        }
+        if let Some(week) = self.busiest_week {
+            let _ = writeln!(out, "Busiest Week: week of {} ({})", week.start.format("%Y-%m-%d"), week.total);
+        }
+        if let Some(month) = self.busiest_month {
+            let _ = writeln!(out, "Busiest Month: {} {} ({})", locale.month(month.start.month()), month.start.year(), month.total);
+        }
+
+        // Day-of-week distribution
+        let _ = writeln!(out, "\nContributions by Weekday");
+        let max_weekday = self.weekday_totals.iter().map(|day| day.total).max().unwrap_or(0);
+        for day in &self.weekday_totals {
+            let label = crate::locale::pad(locale.weekday(day.weekday), 4);
+            let _ = writeln!(out, "  {}{} {}", label, bar(day.total, max_weekday, options), day.total);
+        }
+
+        // Monthly totals
+        let _ = writeln!(out, "\nMonthly Totals");
+        let max_month = self.monthly_totals.iter().map(|month| month.total).max().unwrap_or(0);
+        for month in &self.monthly_totals {
+            let label = crate::locale::pad(locale.month(month.start.month()), 5);
+            let _ = writeln!(out, "  {} {}{} {}", month.start.year(), label, bar(month.total, max_month, options), month.total);
+        }
+
+        // Weekly totals as a sparkline, one line per year of weeks
+        let _ = writeln!(out, "\nWeekly Totals");
+        let max_week = self.weekly_totals.iter().map(|week| week.total).max().unwrap_or(0);
+        for weeks in self.weekly_totals.chunks(SPARKLINE_WEEKS) {
+            let line: String = weeks.iter().map(|week| spark(week.total, max_week)).collect();
+            let _ = writeln!(out, "  {} {}", weeks[0].start.format("%Y-%m-%d"), paint_bar(&line, options));
+        }
+
+        // Year over year
+        if !self.yearly_totals.is_empty() {
+            let _ = writeln!(out, "\nYear over Year");
+            for year in &self.yearly_totals {
+                let change = year.change_percent
+                    .map(|change| format!(" ({:+.1}%)", change))
+                    .unwrap_or_default();
+                let _ = writeln!(out, "  {}: {}{}", year.year, year.total, change);
+            }
+        }
+
+        out.push('\n');
+        out
+    }
+}
+
+// Private contribution totals, shown after the stats block with --private
+pub fn private_to_text(private: &PrivateTotals) -> String {
+    let mut out = String::from("Private Contributions\n");
+    if private.any_restricted {
+        let _ = writeln!(out, "  Hidden from this token: {} (not placed on days)", private.restricted.to_string().green());
+    }
+    if let Some(visible) = private.visible {
+        let _ = writeln!(
+            out,
+            "  In private repositories this token can see: {} (daily counts raised by {})",
+            visible.to_string().green(),
+            private.folded
+        );
+    }
+    if !private.any_restricted && private.visible.is_none() {
+        let _ = writeln!(out, "  None reported");
+    }
+
+    out.push('\n');
+    out
+}
+
+// The same month and day a year earlier, with February 29 falling back to February 28
+fn same_day_year_before(date: NaiveDate) -> NaiveDate {
+    date.with_year(date.year() - 1)
+        .or_else(|| date.pred_opt()?.with_year(date.year() - 1))
+        .unwrap_or(date)
+}
+
+// Describe a run of days for the stats block
+fn describe_span(span: Option<Span>) -> String {
+    match span {
+        Some(span) => format!(
+            "{} days ({} - {})",
+            span.days,
+            span.start.format("%Y-%m-%d"),
+            span.end.format("%Y-%m-%d")
+        ),
+        None => "0 days".to_string(),
+    }
+}
+
+// Horizontal bar scaled to the largest value, padded to a fixed width
+fn bar(value: u32, max: u32, options: &RenderOptions) -> String {
+    let eighths = if max > 0 { (value as usize * BAR_WIDTH * 8) / max as usize } else { 0 };
+    let text = format!("{}{}", "\u{2588}".repeat(eighths / 8), BAR_EIGHTHS[eighths % 8]);
+    let padding = BAR_WIDTH + 1 - eighths / 8 - usize::from(eighths % 8 > 0);
+
+    format!("{}{}", paint_bar(&text, options), " ".repeat(padding))
+}
+
+// Sparkline glyph for a value scaled to the largest value
+fn spark(value: u32, max: u32) -> char {
+    if value == 0 || max == 0 {
+        return ' ';
+    }
+    SPARKS[((value as usize * SPARKS.len()).div_ceil(max as usize) - 1).min(SPARKS.len() - 1)]
+}
+
+// Color chart glyphs with a mid-level theme color
+fn paint_bar(text: &str, options: &RenderOptions) -> String {
+    options.colors.paint(text, Some(options.theme.color(3)), None)
+}
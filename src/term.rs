@@ -0,0 +1,237 @@
+use crate::theme::Rgb;
+use anyhow::Result;
+use crossterm::{cursor, execute, terminal};
+use std::io::{self, IsTerminal};
+
+// When to emit colors, as requested on the command line
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+// Color capability of the output terminal, from least to most capable
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+// Approximate RGB values of the 16 standard colors (xterm defaults)
+const ANSI16_PALETTE: [Rgb; 16] = [
+    Rgb(0, 0, 0),
+    Rgb(205, 0, 0),
+    Rgb(0, 205, 0),
+    Rgb(205, 205, 0),
+    Rgb(0, 0, 238),
+    Rgb(205, 0, 205),
+    Rgb(0, 205, 205),
+    Rgb(229, 229, 229),
+    Rgb(127, 127, 127),
+    Rgb(255, 0, 0),
+    Rgb(0, 255, 0),
+    Rgb(255, 255, 0),
+    Rgb(92, 92, 255),
+    Rgb(255, 0, 255),
+    Rgb(0, 255, 255),
+    Rgb(255, 255, 255),
+];
+
+// Channel values of the 6x6x6 color cube in the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorSupport {
+    // Detect color support from the environment and the --color choice
+    pub fn detect(choice: ColorChoice) -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+        match choice {
+            ColorChoice::Never => return ColorSupport::None,
+            ColorChoice::Always => {}
+            ColorChoice::Auto => {
+                let forced = env("CLICOLOR_FORCE").is_some_and(|value| value != "0");
+                if !forced {
+                    if env("NO_COLOR").is_some() || !std::io::stdout().is_terminal() {
+                        return ColorSupport::None;
+                    }
+                    if env("TERM").as_deref() == Some("dumb") {
+                        return ColorSupport::None;
+                    }
+                }
+            }
+        }
+
+        Self::from_terminal(env("COLORTERM").as_deref(), env("TERM").as_deref())
+    }
+
+    // Determine the color depth from the COLORTERM and TERM variables
+    pub fn from_terminal(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if matches!(colorterm, Some("truecolor") | Some("24bit")) {
+            return ColorSupport::TrueColor;
+        }
+
+        match term {
+            Some(term) if term.contains("direct") => ColorSupport::TrueColor,
+            Some(term) if term.contains("256color") => ColorSupport::Ansi256,
+            _ => ColorSupport::Ansi16,
+        }
+    }
+
+    // Wrap text in escape codes for the given foreground and background colors
+    pub fn paint(&self, text: &str, fg: Option<Rgb>, bg: Option<Rgb>) -> String {
+        if *self == ColorSupport::None || (fg.is_none() && bg.is_none()) {
+            return text.to_string();
+        }
+
+        let mut codes = Vec::new();
+        if let Some(rgb) = fg {
+            codes.push(self.code(rgb, false));
+        }
+        if let Some(rgb) = bg {
+            codes.push(self.code(rgb, true));
+        }
+
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+
+    // SGR parameters selecting a color at this depth
+    fn code(&self, rgb: Rgb, background: bool) -> String {
+        match self {
+            ColorSupport::TrueColor => {
+                let prefix = if background { 48 } else { 38 };
+                format!("{};2;{};{};{}", prefix, rgb.0, rgb.1, rgb.2)
+            }
+            ColorSupport::Ansi256 => {
+                let prefix = if background { 48 } else { 38 };
+                format!("{};5;{}", prefix, nearest_ansi256(rgb))
+            }
+            ColorSupport::Ansi16 | ColorSupport::None => {
+                let index = nearest_ansi16(rgb);
+                let base = match (background, index < 8) {
+                    (false, true) => 30,
+                    (false, false) => 90 - 8,
+                    (true, true) => 40,
+                    (true, false) => 100 - 8,
+                };
+                (base + index).to_string()
+            }
+        }
+    }
+}
+
+// Width of the output terminal in columns, if it can be determined
+pub fn terminal_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS").ok().and_then(|value| value.parse().ok()) {
+        return Some(columns);
+    }
+
+    terminal_size::terminal_size_of(std::io::stdout())
+        .map(|(terminal_size::Width(width), _)| width as usize)
+}
+
+// Squared distance between two colors
+fn distance(a: Rgb, b: Rgb) -> u32 {
+    let channel = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+// Find the closest entry in the 256-color palette (color cube or grayscale ramp)
+fn nearest_ansi256(rgb: Rgb) -> u8 {
+    let cube_index = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - value as i32).abs())
+            .unwrap_or(0)
+    };
+    let (r, g, b) = (cube_index(rgb.0), cube_index(rgb.1), cube_index(rgb.2));
+    let cube = Rgb(CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    // Grayscale ramp runs from 8 to 238 in steps of 10
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_value = 8 + gray_index * 10;
+    let gray = Rgb(gray_value, gray_value, gray_value);
+
+    if distance(rgb, gray) < distance(rgb, cube) {
+        232 + gray_index
+    } else {
+        16 + (36 * r + 6 * g + b) as u8
+    }
+}
+
+// Perceived lightness of a color, from 0 to 255000
+fn lightness(rgb: Rgb) -> u32 {
+    299 * rgb.0 as u32 + 587 * rgb.1 as u32 + 114 * rgb.2 as u32
+}
+
+// Standard colors for a ramp of theme levels. Nearest matching alone can give two levels the same
+// color or break their order, so each level gets its own color, ordered by lightness like the
+// levels, and as close to the level as that allows. Black is left out so no level disappears on
+// a dark background. A ramp longer than the palette falls back to nearest matching
+pub fn ansi16_ramp(levels: &[Rgb]) -> Vec<Rgb> {
+    let mut candidates: Vec<Rgb> = ANSI16_PALETTE[1..].to_vec();
+    if levels.len() > candidates.len() {
+        return levels.iter().map(|rgb| ANSI16_PALETTE[nearest_ansi16(*rgb) as usize]).collect();
+    }
+    let darkening = match (levels.first(), levels.last()) {
+        (Some(first), Some(last)) => lightness(*last) < lightness(*first),
+        _ => false,
+    };
+    candidates.sort_by_key(|rgb| lightness(*rgb));
+    if darkening {
+        candidates.reverse();
+    }
+
+    // cost[i][j]: best total distance with level i on candidate j and earlier levels on earlier candidates
+    let mut cost = vec![vec![u32::MAX; candidates.len()]; levels.len()];
+    let mut previous = vec![vec![0; candidates.len()]; levels.len()];
+    for (i, level) in levels.iter().enumerate() {
+        for j in i..candidates.len() {
+            let here = distance(*level, candidates[j]);
+            if i == 0 {
+                cost[i][j] = here;
+                continue;
+            }
+            if let Some(k) = (i - 1..j).min_by_key(|&k| cost[i - 1][k]) {
+                cost[i][j] = cost[i - 1][k].saturating_add(here);
+                previous[i][j] = k;
+            }
+        }
+    }
+
+    let mut ramp = vec![Rgb(0, 0, 0); levels.len()];
+    let last = levels.len().saturating_sub(1);
+    let mut j = (0..candidates.len()).min_by_key(|&j| cost[last][j]).unwrap_or(0);
+    for i in (0..levels.len()).rev() {
+        ramp[i] = candidates[j];
+        j = previous[i][j];
+    }
+    ramp
+}
+
+// Find the closest of the 16 standard colors
+fn nearest_ansi16(rgb: Rgb) -> u8 {
+    (0..ANSI16_PALETTE.len())
+        .min_by_key(|&i| distance(rgb, ANSI16_PALETTE[i]))
+        .unwrap_or(0) as u8
+}
+
+// Raw mode on the alternate screen for full-screen views, restored when dropped
+pub struct AlternateScreen;
+
+impl AlternateScreen {
+    pub fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for AlternateScreen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
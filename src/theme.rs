@@ -0,0 +1,122 @@
+use crate::config::Config;
+use crate::error::GhHeatError;
+use crate::term::{self, ColorSupport};
+use anyhow::Result;
+
+// Name of the theme used when none is requested
+pub const DEFAULT_THEME: &str = "github";
+
+// An RGB color used by theme palettes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    // Parse a "#rrggbb" or "rrggbb" hex string
+    pub fn from_hex(hex: &str) -> Result<Self, GhHeatError> {
+        let digits = hex.trim().trim_start_matches('#');
+        let invalid = || GhHeatError::Config(format!("invalid hex color '{}'", hex));
+
+        if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| invalid());
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    // Halfway between this color and mid gray
+    pub fn dim(self) -> Self {
+        let mix = |channel: u8| ((channel as u16 + 128) / 2) as u8;
+        Rgb(mix(self.0), mix(self.1), mix(self.2))
+    }
+}
+
+// A named color palette for contribution intensities
+#[derive(Clone, Debug)]
+pub struct Theme {
+    // Background for days without contributions (None keeps the terminal background)
+    pub empty: Option<Rgb>,
+    // Colors from the lowest to the highest intensity
+    pub levels: Vec<Rgb>,
+}
+
+// Built-in palettes as (name, hex colors from low to high intensity)
+const BUILTIN_THEMES: &[(&str, [&str; 5])] = &[
+    ("github", ["#9be9a8", "#40c463", "#30a14e", "#216e39", "#0e4429"]),
+    ("github-dark", ["#0e4429", "#006d32", "#26a641", "#39d353", "#7ee787"]),
+    ("halloween", ["#fff3a3", "#ffee4a", "#ffc501", "#fe9600", "#03001c"]),
+    ("ice", ["#e0f3ff", "#a6d8ff", "#5fb3f0", "#2a7fd0", "#0a4a8f"]),
+    ("grayscale", ["#d9d9d9", "#a6a6a6", "#737373", "#404040", "#1a1a1a"]),
+    ("viridis", ["#440154", "#3b528b", "#21918c", "#5ec962", "#fde725"]),
+    ("cividis", ["#00224e", "#3d4d6e", "#7f7c75", "#bcaf6f", "#fee838"]),
+];
+
+// Diverging palette for difference heatmaps, from weak to strong: orange where
+// the first heatmap has more contributions, purple where the second one does
+pub const DIVERGING_FEWER: [Rgb; 3] = [Rgb(0xfd, 0xb8, 0x63), Rgb(0xe0, 0x82, 0x14), Rgb(0xb3, 0x58, 0x06)];
+pub const DIVERGING_MORE: [Rgb; 3] = [Rgb(0xb2, 0xab, 0xd2), Rgb(0x80, 0x73, 0xac), Rgb(0x54, 0x27, 0x88)];
+
+impl Theme {
+    // Look up a theme by name, preferring user-defined themes over built-ins
+    pub fn load(name: &str, config: &Config) -> Result<Self> {
+        if let Some(custom) = config.themes.get(name) {
+            let levels = custom.levels.iter()
+                .map(|hex| Rgb::from_hex(hex))
+                .collect::<Result<Vec<_>, _>>()?;
+            if levels.is_empty() {
+                return Err(GhHeatError::Config(format!("theme '{}' has no levels", name)).into());
+            }
+            let empty = custom.empty.as_deref().map(Rgb::from_hex).transpose()?;
+
+            return Ok(Self { empty, levels });
+        }
+
+        if let Some((_, colors)) = BUILTIN_THEMES.iter().find(|(builtin, _)| *builtin == name) {
+            let levels = colors.iter()
+                .map(|hex| Rgb::from_hex(hex).expect("built-in theme colors are valid"))
+                .collect();
+            return Ok(Self { empty: None, levels });
+        }
+
+        Err(GhHeatError::Config(format!(
+            "unknown theme '{}' (available: {})",
+            name,
+            Self::names(config).join(", ")
+        )).into())
+    }
+
+    // Names of the built-in themes followed by the custom ones from the config file
+    pub fn names(config: &Config) -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_THEMES.iter().map(|(builtin, _)| builtin.to_string()).collect();
+        let mut custom: Vec<String> = config.themes.keys()
+            .filter(|name| !names.contains(name))
+            .cloned()
+            .collect();
+        custom.sort();
+        names.extend(custom);
+        names
+    }
+
+    // A muted copy of the theme, for days drawn in the background like weekends
+    pub fn dimmed(&self) -> Self {
+        Self {
+            empty: self.empty.map(Rgb::dim),
+            levels: self.levels.iter().map(|rgb| rgb.dim()).collect(),
+        }
+    }
+
+    // The theme as the terminal can show it: 16-color terminals get a ramp of distinct standard
+    // colors, since nearest matching can merge levels
+    pub fn for_colors(&self, colors: ColorSupport) -> Self {
+        match colors {
+            ColorSupport::Ansi16 => Self { empty: self.empty, levels: term::ansi16_ramp(&self.levels) },
+            _ => self.clone(),
+        }
+    }
+
+    // Color for an intensity level from 1 (lowest) to 5 (highest)
+    pub fn color(&self, intensity: usize) -> Rgb {
+        let step = intensity.clamp(1, 5) - 1;
+        self.levels[step * self.levels.len() / 5]
+    }
+}
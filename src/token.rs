@@ -0,0 +1,186 @@
+use crate::error::GhHeatError;
+use anyhow::Result;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// Host that tokens are looked up for
+pub const GITHUB_HOST: &str = "github.com";
+
+// Where a token was found
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TokenSource {
+    File(PathBuf),
+    // An environment variable: GH_TOKEN, GITHUB_TOKEN or their GH_ENTERPRISE_ forms
+    Env(&'static str),
+    // The gh CLI's hosts.yml
+    GhHosts(PathBuf),
+    // `gh auth token`, for gh versions that keep the token in the system keyring
+    GhKeyring,
+    GitCredential,
+}
+
+impl fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenSource::File(path) => write!(f, "--token-file {}", path.display()),
+            TokenSource::Env(name) => write!(f, "the {} environment variable", name),
+            TokenSource::GhHosts(path) => write!(f, "gh CLI config {}", path.display()),
+            TokenSource::GhKeyring => write!(f, "`gh auth token`"),
+            TokenSource::GitCredential => write!(f, "`git credential fill`"),
+        }
+    }
+}
+
+// A token and where it came from. Debug output leaves the token out
+#[derive(Clone)]
+pub struct Token {
+    pub value: String,
+    pub source: TokenSource,
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Token").field("value", &"<redacted>").field("source", &self.source).finish()
+    }
+}
+
+// Find a token for a host, trying in order: --token-file, GH_TOKEN and GITHUB_TOKEN (or their
+// GH_ENTERPRISE_ forms for Enterprise Server), the gh CLI and git's credential helpers.
+// Only an unreadable --token-file is an error
+pub fn discover(host: &str, token_file: Option<&Path>) -> Result<Option<Token>> {
+    if let Some(path) = token_file {
+        let value = read_token_file(path)?;
+        return Ok(Some(Token { value, source: TokenSource::File(path.to_path_buf()) }));
+    }
+
+    Ok(from_env(host)
+        .or_else(|| from_gh(host))
+        .or_else(|| from_git_credential(host)))
+}
+
+// The token variable for a host, in the order the gh CLI checks them
+pub fn from_env(host: &str) -> Option<Token> {
+    from_vars(host, |name| std::env::var(name).ok())
+}
+
+// GH_TOKEN or GITHUB_TOKEN for github.com and GHE.com, and GH_ENTERPRISE_TOKEN or
+// GITHUB_ENTERPRISE_TOKEN for Enterprise Server hosts, so a github.com token never goes elsewhere
+pub fn from_vars(host: &str, var: impl Fn(&str) -> Option<String>) -> Option<Token> {
+    let names = if is_enterprise(host) {
+        ["GH_ENTERPRISE_TOKEN", "GITHUB_ENTERPRISE_TOKEN"]
+    } else {
+        ["GH_TOKEN", "GITHUB_TOKEN"]
+    };
+    names.into_iter().find_map(|name| {
+        let value = var(name).filter(|value| !value.trim().is_empty())?;
+        Some(Token { value: value.trim().to_string(), source: TokenSource::Env(name) })
+    })
+}
+
+// A GitHub Enterprise Server host, as opposed to github.com or a GHE.com tenant
+fn is_enterprise(host: &str) -> bool {
+    let host = host.to_lowercase();
+    host != GITHUB_HOST && !host.ends_with(".ghe.com")
+}
+
+// The first line of a file, which must not be empty
+pub fn read_token_file(path: &Path) -> Result<String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| GhHeatError::Config(format!("{}: {}", path.display(), e)))?;
+    contents.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| GhHeatError::Config(format!("{}: no token in file", path.display())).into())
+}
+
+// The oauth_token of a host in gh's hosts.yml: the active account's token directly under the host,
+// otherwise the first one listed under its users
+pub fn parse_gh_hosts(contents: &str, host: &str) -> Option<String> {
+    let mut in_host = false;
+    let mut best: Option<(usize, String)> = None;
+    for line in contents.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if indent == 0 {
+            in_host = unquote(line.trim_end_matches(':')) == host;
+            continue;
+        }
+        if !in_host {
+            continue;
+        }
+
+        if let Some(value) = line.strip_prefix("oauth_token:") {
+            let value = unquote(value.trim());
+            if !value.is_empty() && best.as_ref().is_none_or(|(best_indent, _)| indent < *best_indent) {
+                best = Some((indent, value.to_string()));
+            }
+        }
+    }
+
+    best.map(|(_, token)| token)
+}
+
+// Location of gh's hosts.yml, following GH_CONFIG_DIR and XDG_CONFIG_HOME like gh does
+fn gh_hosts_path() -> Option<PathBuf> {
+    let env_dir = |name: &str| std::env::var_os(name).filter(|dir| !dir.is_empty()).map(PathBuf::from);
+    let dir = env_dir("GH_CONFIG_DIR")
+        .or_else(|| env_dir("XDG_CONFIG_HOME").map(|dir| dir.join("gh")))
+        .or_else(|| env_dir("APPDATA").map(|dir| dir.join("GitHub CLI")))
+        .or_else(|| env_dir("HOME").map(|home| home.join(".config").join("gh")))?;
+
+    Some(dir.join("hosts.yml"))
+}
+
+// A token from gh's hosts.yml, or from `gh auth token` when gh keeps it in the keyring
+fn from_gh(host: &str) -> Option<Token> {
+    if let Some(path) = gh_hosts_path() {
+        let token = std::fs::read_to_string(&path).ok().and_then(|contents| parse_gh_hosts(&contents, host));
+        if let Some(value) = token {
+            return Some(Token { value, source: TokenSource::GhHosts(path) });
+        }
+    }
+
+    let output = Command::new("gh")
+        .args(["auth", "token", "--hostname", host])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!value.is_empty()).then_some(Token { value, source: TokenSource::GhKeyring })
+}
+
+// A password for the host from git's credential helpers, without ever prompting
+fn from_git_credential(host: &str) -> Option<Token> {
+    let mut child = Command::new("git")
+        .args(["-c", "core.askPass=", "credential", "fill"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "never")
+        .env_remove("GIT_ASKPASS")
+        .env_remove("SSH_ASKPASS")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(format!("protocol=https\nhost={}\n\n", host).as_bytes()).ok()?;
+
+    let output = child.wait_with_output().ok().filter(|output| output.status.success())?;
+    let value = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("password="))?
+        .trim()
+        .to_string();
+    (!value.is_empty()).then_some(Token { value, source: TokenSource::GitCredential })
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches(|c| c == '"' || c == '\'')
+}
@@ -0,0 +1,243 @@
+use crate::activity::{self, Activity, DaySummary};
+use crate::config::Config;
+use crate::heatmap::{Heatmap, Orientation, RenderMode, RenderOptions};
+use crate::locale;
+use crate::term::{AlternateScreen, ColorSupport};
+use crate::theme::Theme;
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+use colored::Colorize;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, queue, style, terminal};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+// Width of the side panel with the selected day's details
+const PANEL_WIDTH: usize = 34;
+
+// Narrowest heatmap area before the grid is wrapped regardless of the panel
+const MIN_GRID_WIDTH: usize = 24;
+
+// Ranges in days to switch between, besides the fetched range
+const RANGES: [u32; 4] = [30, 90, 180, 365];
+
+// Key help shown on the last line
+const HELP: &str = "arrows/hjkl move  t/T theme  r/R range  g today  q quit";
+
+// Full-screen heatmap with a cursor and the selected day's details
+pub struct App<'a> {
+    username: &'a str,
+    heatmap: Heatmap,
+    activity: HashMap<NaiveDate, DaySummary>,
+    config: &'a Config,
+    options: RenderOptions<'a>,
+    theme_names: Vec<String>,
+    theme_idx: usize,
+    theme: Theme,
+    // Ranges in days from the shortest to the fetched one
+    ranges: Vec<u32>,
+    range_idx: usize,
+    today: NaiveDate,
+    cursor: NaiveDate,
+}
+
+impl<'a> App<'a> {
+    pub fn new(
+        username: &'a str,
+        heatmap: Heatmap,
+        activities: &[Activity],
+        (start_date, today): (NaiveDate, NaiveDate),
+        theme_name: &str,
+        config: &'a Config,
+        options: RenderOptions<'a>,
+    ) -> Result<Self> {
+        // The cursor needs one cell per day, so compact modes are drawn as blocks
+        let mode = match options.mode {
+            mode if !mode.is_compact() => mode,
+            _ if options.colors == ColorSupport::None => RenderMode::Symbols,
+            _ => RenderMode::Blocks,
+        };
+        let options = RenderOptions { mode, orientation: Orientation::Horizontal, max_width: None, ..options };
+
+        let theme_names = Theme::names(config);
+        let theme_idx = theme_names.iter().position(|name| name == theme_name).unwrap_or(0);
+        let days = (today - start_date).num_days() as u32;
+        let mut ranges: Vec<u32> = RANGES.iter().copied().filter(|range| *range < days).collect();
+        ranges.push(days);
+
+        Ok(Self {
+            username,
+            heatmap,
+            activity: activity::by_day(activities),
+            config,
+            options,
+            theme: Theme::load(theme_name, config)?.for_colors(options.colors),
+            theme_names,
+            theme_idx,
+            range_idx: ranges.len() - 1,
+            ranges,
+            today,
+            cursor: today,
+        })
+    }
+
+    // Show the view until the user quits
+    pub fn run(&mut self) -> Result<()> {
+        let _screen = AlternateScreen::enter()?;
+        let mut stdout = io::stdout();
+
+        loop {
+            self.draw(&mut stdout)?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.handle_key(key)? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    // Apply a key press, returning false when the view should close
+    fn handle_key(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(false),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(-7),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(7),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+            KeyCode::Home | KeyCode::Char('g') => self.cursor = self.today,
+            KeyCode::Char('t') => self.switch_theme(1)?,
+            KeyCode::Char('T') => self.switch_theme(self.theme_names.len() - 1)?,
+            KeyCode::Char('r') => self.switch_range(1),
+            KeyCode::Char('R') => self.switch_range(self.ranges.len() - 1),
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    // First day of the selected range
+    fn start_date(&self) -> NaiveDate {
+        self.today - Duration::days(self.ranges[self.range_idx] as i64)
+    }
+
+    // Move the cursor by a number of days, staying inside the range
+    fn move_cursor(&mut self, days: i64) {
+        self.cursor = (self.cursor + Duration::days(days)).clamp(self.start_date(), self.today);
+    }
+
+    // Cycle forward through the themes by a step
+    fn switch_theme(&mut self, step: usize) -> Result<()> {
+        self.theme_idx = (self.theme_idx + step) % self.theme_names.len();
+        self.theme = Theme::load(&self.theme_names[self.theme_idx], self.config)?.for_colors(self.options.colors);
+        Ok(())
+    }
+
+    // Cycle forward through the ranges by a step
+    fn switch_range(&mut self, step: usize) {
+        self.range_idx = (self.range_idx + step) % self.ranges.len();
+        self.move_cursor(0);
+    }
+
+    // Redraw the heatmap, the side panel and the key help
+    fn draw(&self, out: &mut impl Write) -> Result<()> {
+        let (columns, rows) = terminal::size()?;
+        let (columns, rows) = (columns as usize, rows as usize);
+
+        let view = self.heatmap.between(self.start_date(), self.today);
+        let options = RenderOptions {
+            theme: &self.theme,
+            max_width: Some(columns.saturating_sub(PANEL_WIDTH + 2).max(MIN_GRID_WIDTH)),
+            cursor: Some(self.cursor),
+            ..self.options
+        };
+        let rendered = view.render_to_string(&options);
+        let lines: Vec<&str> = rendered.lines().collect();
+        let grid_width = lines.iter().map(|line| visible_width(line)).max().unwrap_or(0);
+
+        queue!(out, terminal::Clear(terminal::ClearType::All))?;
+        let title = format!("{}  {}", "gh-heat".bold(), self.username.bright_white().bold());
+        queue!(out, cursor::MoveTo(0, 0), style::Print(title))?;
+        for (row, line) in lines.iter().enumerate().take(rows.saturating_sub(2)) {
+            queue!(out, cursor::MoveTo(0, row as u16 + 1), style::Print(line))?;
+        }
+
+        // The panel sits right of the grid, or below it when the terminal is too narrow
+        let (panel_column, panel_row) = if grid_width + 2 + PANEL_WIDTH <= columns {
+            (grid_width + 2, 2)
+        } else {
+            (0, lines.len() + 1)
+        };
+        for (row, line) in self.panel_lines().iter().enumerate() {
+            let row = panel_row + row;
+            if row >= rows.saturating_sub(1) {
+                break;
+            }
+            queue!(out, cursor::MoveTo(panel_column as u16, row as u16), style::Print(line))?;
+        }
+
+        queue!(out, cursor::MoveTo(0, rows.saturating_sub(1) as u16), style::Print(HELP.dimmed()))?;
+        out.flush()?;
+        Ok(())
+    }
+
+    // Details of the day under the cursor
+    fn panel_lines(&self) -> Vec<String> {
+        let locale = self.options.locale;
+        let count = self.heatmap.contributions().get(&self.cursor).copied().unwrap_or(0);
+        let mut lines = vec![
+            format!("{} {}", locale.weekday(self.cursor.weekday()), self.cursor.format("%Y-%m-%d")).bold().to_string(),
+            format!("{} contributions", count),
+        ];
+        let overlays = self.options.overlays;
+        for label in [overlays.holidays.get(&self.cursor), overlays.markers.get(&self.cursor)].into_iter().flatten() {
+            lines.push(truncate(label, PANEL_WIDTH).yellow().to_string());
+        }
+        lines.push(String::new());
+
+        match self.activity.get(&self.cursor) {
+            Some(summary) => {
+                for (kind, total) in &summary.kinds {
+                    lines.push(format!("{}{:>5}", locale::pad(kind.label(), PANEL_WIDTH - 6), total));
+                }
+                lines.push(String::new());
+                lines.push("Repositories".bold().to_string());
+                for (repository, total) in summary.busiest_repositories() {
+                    let name = truncate(repository, PANEL_WIDTH - 8);
+                    lines.push(format!("  {}{:>5}", locale::pad(&name, PANEL_WIDTH - 8), total));
+                }
+            }
+            None if count > 0 => lines.push("No activity details for this day".dimmed().to_string()),
+            None => {}
+        }
+
+        lines.push(String::new());
+        lines.push(format!("Theme  {}", self.theme_names[self.theme_idx]));
+        lines.push(format!("Range  {} days", self.ranges[self.range_idx]));
+        lines
+    }
+}
+
+// Display width of a line, ignoring color escape codes
+fn visible_width(line: &str) -> usize {
+    let mut in_escape = false;
+    let mut plain = String::new();
+    for c in line.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            'm' if in_escape => in_escape = false,
+            _ if in_escape => {}
+            _ => plain.push(c),
+        }
+    }
+    locale::display_width(&plain)
+}
+
+// Shorten a name to a number of characters, marking the cut with an ellipsis
+fn truncate(name: &str, width: usize) -> String {
+    if name.chars().count() <= width {
+        return name.to_string();
+    }
+    let mut truncated: String = name.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('\u{2026}');
+    truncated
+}
@@ -0,0 +1,166 @@
+use crate::cache::Cache;
+use crate::clock::Clock;
+use crate::github::GithubClient;
+use crate::heatmap::{Fit, Heatmap, RenderOptions, WeekStart};
+use crate::term::AlternateScreen;
+use anyhow::Result;
+use chrono::NaiveDate;
+use colored::Colorize;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, queue, style, terminal};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+// Number of refreshes a new contribution stays highlighted
+const HIGHLIGHT_REFRESHES: u32 = 5;
+
+// Parse a refresh interval like "90", "30s", "5m" or "1h"
+pub fn parse_interval(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => value.split_at(idx),
+        None => (value, "s"),
+    };
+    let number: u64 = number.parse().map_err(|_| format!("invalid interval '{}'", value))?;
+    let seconds = match unit {
+        "s" => number,
+        "m" => number * 60,
+        "h" => number * 3600,
+        _ => return Err(format!("invalid interval unit in '{}' (use s, m or h)", value)),
+    };
+    if seconds == 0 {
+        return Err("interval must be at least one second".to_string());
+    }
+
+    Ok(Duration::from_secs(seconds))
+}
+
+// Heatmap that refetches the current week on an interval and highlights new contributions
+pub struct Watch<'a> {
+    pub client: &'a GithubClient,
+    pub username: &'a str,
+    pub days: u32,
+    pub week_start: WeekStart,
+    pub interval: Duration,
+    pub clock: Clock,
+    // Width given with --width, which stays when the terminal is resized
+    pub width: Option<usize>,
+}
+
+impl Watch<'_> {
+    // Redraw on the alternate screen until the user quits. The client should be quiet,
+    // since anything it prints would land on the alternate screen
+    pub fn run(&self, mut options: RenderOptions, fit: Fit) -> Result<()> {
+        // Start from the cache, then bring the whole range up to date
+        let mut cache = Cache::load(self.username)?;
+        cache.merge(&self.client.get_user_contributions(self.username, self.days)?.counts, self.clock.now_utc());
+        cache.save()?;
+
+        let _screen = AlternateScreen::enter()?;
+        let mut stdout = io::stdout();
+        let mut highlights: HashMap<NaiveDate, u32> = HashMap::new();
+        let mut status = String::new();
+
+        loop {
+            self.draw(&mut stdout, &cache, &highlights, &status, options, fit)?;
+
+            // Wait for the next refresh, redrawing on resize and stopping on q, Esc or Ctrl-C
+            let deadline = Instant::now() + self.interval;
+            while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+                if !event::poll(remaining)? {
+                    break;
+                }
+                match event::read()? {
+                    Event::Key(key) if key.kind == KeyEventKind::Press => {
+                        let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                        if ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                            return Ok(());
+                        }
+                    }
+                    Event::Resize(columns, _) => {
+                        // No width limit means --fit none or the vertical layout, which don't fit to the terminal
+                        if options.max_width.is_some() && self.width.is_none() {
+                            options.max_width = Some(columns as usize);
+                        }
+                        self.draw(&mut stdout, &cache, &highlights, &status, options, fit)?;
+                    }
+                    _ => {}
+                }
+            }
+
+            // Older highlights fade after a few refreshes
+            highlights.retain(|_, remaining| {
+                *remaining -= 1;
+                *remaining > 0
+            });
+            status = match self.refresh(&mut cache) {
+                Ok((increased, fallback)) => {
+                    let count = increased.len();
+                    highlights.extend(increased.into_iter().map(|date| (date, HIGHLIGHT_REFRESHES)));
+                    let mut notes = Vec::new();
+                    if count > 0 {
+                        notes.push(format!("{} day(s) with new contributions", count));
+                    }
+                    if let Some(error) = fallback {
+                        notes.push(format!("GraphQL failed ({}), using the public contributions page", error));
+                    }
+                    notes.join("; ")
+                }
+                Err(err) => format!("Refresh failed: {}", err),
+            };
+        }
+    }
+
+    // Fetch the current week and merge it into the cache, returning the days that went up
+    // and why GraphQL was skipped, if it was
+    pub fn refresh(&self, cache: &mut Cache) -> Result<(Vec<NaiveDate>, Option<String>)> {
+        let today = self.clock.today();
+        let start_date = Heatmap::week_containing(today, self.week_start.weekday());
+
+        let contributions = self.client.get_user_contributions_between(self.username, start_date, today)?;
+        let increased = cache.merge(&contributions.counts, self.clock.now_utc());
+        cache.save()?;
+
+        Ok((increased, contributions.fallback))
+    }
+
+    // Clear the screen and draw the heatmap with a status line
+    fn draw(
+        &self,
+        out: &mut impl Write,
+        cache: &Cache,
+        highlights: &HashMap<NaiveDate, u32>,
+        status: &str,
+        options: RenderOptions,
+        fit: Fit,
+    ) -> Result<()> {
+        let today = self.clock.today();
+        let heatmap = Heatmap::new(cache.contributions(), self.week_start, today)
+            .between(today - chrono::Duration::days(self.days as i64), today);
+
+        let highlighted: Vec<NaiveDate> = highlights.keys().copied().collect();
+        let mut options = RenderOptions { highlights: &highlighted, ..options };
+        if let (Fit::Compact, Some(max_width)) = (fit, options.max_width) {
+            options.mode = heatmap.fitting_mode(options.mode, max_width);
+        }
+
+        queue!(out, terminal::Clear(terminal::ClearType::All), cursor::MoveTo(0, 0))?;
+        let updated = cache.updated
+            .map(|updated| self.clock.zone().convert(&updated).format("%H:%M:%S").to_string())
+            .unwrap_or_default();
+        let title = format!(
+            "{}  every {}s, updated {}, {} contributions  (q to quit)",
+            self.username.bright_white().bold(),
+            self.interval.as_secs(),
+            updated,
+            heatmap.total_contributions()
+        );
+
+        // Raw mode does not return the carriage on a newline
+        let rendered = format!("{}\n{}\n{}", title, heatmap.render_to_string(&options), status.yellow());
+        queue!(out, style::Print(rendered.replace('\n', "\r\n")))?;
+        out.flush()?;
+        Ok(())
+    }
+}
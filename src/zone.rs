@@ -0,0 +1,82 @@
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+// Time zone that days are bucketed in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Zone {
+    #[default]
+    Local,
+    // An IANA zone like "America/Los_Angeles"
+    Named(Tz),
+    // A fixed offset like "+05:30"
+    Fixed(FixedOffset),
+}
+
+// Parse "local", an IANA zone name or a UTC offset like "+05:30", "-0800" or "-8"
+pub fn parse(value: &str) -> Result<Zone, String> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("local") {
+        return Ok(Zone::Local);
+    }
+
+    if let Some(sign) = value.chars().next().filter(|c| *c == '+' || *c == '-') {
+        let digits = &value[1..];
+        let (hours, minutes) = match digits.split_once(':') {
+            Some((hours, minutes)) => (hours, minutes),
+            None if digits.len() == 4 => digits.split_at(2),
+            None => (digits, "0"),
+        };
+        let invalid = || format!("invalid UTC offset '{}' (use e.g. +05:30 or -8)", value);
+        let hours: i32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+        if minutes >= 60 {
+            return Err(invalid());
+        }
+        let seconds = (hours * 3600 + minutes * 60) * if sign == '-' { -1 } else { 1 };
+        return FixedOffset::east_opt(seconds).map(Zone::Fixed).ok_or_else(invalid);
+    }
+
+    value.parse::<Tz>()
+        .map(Zone::Named)
+        .map_err(|_| format!("unknown time zone '{}' (use local, a name like Europe/Berlin, or an offset like +02:00)", value))
+}
+
+impl Zone {
+    // A timestamp as seen in the zone
+    pub fn convert<T: TimeZone>(self, timestamp: &DateTime<T>) -> DateTime<FixedOffset> {
+        match self {
+            Zone::Local => timestamp.with_timezone(&Local).fixed_offset(),
+            Zone::Named(tz) => timestamp.with_timezone(&tz).fixed_offset(),
+            Zone::Fixed(offset) => timestamp.with_timezone(&offset),
+        }
+    }
+
+    // The day a timestamp falls on in the zone
+    pub fn date_of<T: TimeZone>(self, timestamp: &DateTime<T>) -> NaiveDate {
+        self.convert(timestamp).date_naive()
+    }
+
+    // The first moment of a day in the zone
+    pub fn start_of_day(self, date: NaiveDate) -> DateTime<FixedOffset> {
+        self.at(date.and_time(NaiveTime::MIN))
+    }
+
+    // The last second of a day in the zone
+    pub fn end_of_day(self, date: NaiveDate) -> DateTime<FixedOffset> {
+        self.start_of_day(date + Duration::days(1)) - Duration::seconds(1)
+    }
+
+    // A wall-clock time in the zone, taking the earlier moment when clocks go back
+    fn at(self, local: NaiveDateTime) -> DateTime<FixedOffset> {
+        let resolve = |local: NaiveDateTime| match self {
+            Zone::Local => Local.from_local_datetime(&local).earliest().map(|time| time.fixed_offset()),
+            Zone::Named(tz) => tz.from_local_datetime(&local).earliest().map(|time| time.fixed_offset()),
+            Zone::Fixed(offset) => offset.from_local_datetime(&local).earliest(),
+        };
+
+        // A time skipped when clocks go forward resolves to the moment they jumped
+        resolve(local)
+            .or_else(|| resolve(local + Duration::hours(1)))
+            .unwrap_or_else(|| self.convert(&Utc.from_utc_datetime(&local)))
+    }
+}
@@ -0,0 +1,79 @@
+use std::process::{Command, Output};
+
+fn gh_heat(args: &[&str], env: &[(&str, &std::path::Path)]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_gh-heat"));
+    command.args(args);
+    for (name, value) in env {
+        command.env(name, value);
+    }
+    command.output().unwrap()
+}
+
+#[test]
+fn completions_and_man_page() {
+    for shell in ["bash", "zsh", "fish", "powershell", "elvish"] {
+        let output = gh_heat(&["completions", shell], &[]);
+        assert!(output.status.success(), "{}", shell);
+        let script = String::from_utf8_lossy(&output.stdout);
+        assert!(script.contains("gh-heat") && script.contains("compare"), "{}", shell);
+    }
+
+    let output = gh_heat(&["man"], &[]);
+    assert!(output.status.success());
+    let page = String::from_utf8_lossy(&output.stdout);
+    assert!(page.contains(".TH gh-heat 1"));
+    assert!(page.contains("completions"));
+}
+
+#[test]
+fn username_required_without_command() {
+    let output = gh_heat(&[], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("<USERNAME>"));
+
+    // Show options belong to show, not to other commands, on either side of them
+    let output = gh_heat(&["stats", "alice", "--totals"], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = gh_heat(&["--totals", "cache", "path"], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'cache' cannot be used with '--totals'"));
+}
+
+// Global options work before a command as well as after it
+#[test]
+fn global_options_before_command() {
+    let dir = std::env::temp_dir().join(format!("gh-heat-cli-config-{}", std::process::id()));
+
+    for args in [["--days", "30", "config", "show"], ["config", "show", "--days", "30"]] {
+        let output = gh_heat(&args, &[("XDG_CONFIG_HOME", &dir)]);
+        assert!(output.status.success(), "{:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("days = 30"), "{:?}", args);
+    }
+
+    let output = gh_heat(&["--source", "scrape", "cache", "path"], &[("XDG_CACHE_HOME", &dir)]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), dir.join("gh-heat").display().to_string());
+}
+
+#[test]
+fn cache_path_and_clear() {
+    let dir = std::env::temp_dir().join(format!("gh-heat-cli-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("gh-heat")).unwrap();
+    std::fs::write(dir.join("gh-heat/alice.json"), "{}").unwrap();
+    std::fs::write(dir.join("gh-heat/bob.json"), "{}").unwrap();
+
+    let output = gh_heat(&["cache", "path"], &[("XDG_CACHE_HOME", &dir)]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), dir.join("gh-heat").display().to_string());
+
+    let output = gh_heat(&["cache", "clear", "Alice"], &[("XDG_CACHE_HOME", &dir)]);
+    assert!(output.status.success());
+    assert!(!dir.join("gh-heat/alice.json").exists());
+    assert!(dir.join("gh-heat/bob.json").exists());
+
+    let output = gh_heat(&["cache", "clear"], &[("XDG_CACHE_HOME", &dir)]);
+    let remaining = std::fs::read_dir(dir.join("gh-heat")).unwrap().count();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(remaining, 0);
+}
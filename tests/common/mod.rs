@@ -0,0 +1,174 @@
+// A minimal HTTP stand-in for github.com and api.github.com, serving canned responses
+// and recording the requests it receives.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// A canned response
+#[derive(Clone, Debug)]
+pub struct Reply {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Reply {
+    pub fn new(status: u16, body: &str) -> Self {
+        Self { status, headers: Vec::new(), body: body.to_string() }
+    }
+
+    // A 200 response with the contents of a file in tests/fixtures/github
+    pub fn fixture(name: &str) -> Self {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/github").join(name);
+        Self::new(200, &std::fs::read_to_string(path).unwrap())
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+// A request as received by the stand-in
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: String,
+    // Path with the query string
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    // GraphQL variables of a POST to /graphql
+    pub fn variables(&self) -> serde_json::Value {
+        let body: serde_json::Value = serde_json::from_str(&self.body).unwrap();
+        body["variables"].clone()
+    }
+}
+
+// Serve a reply to requests whose method matches, whose target starts with a prefix
+// and, if given, whose body contains a marker such as a GraphQL field name
+struct Route {
+    method: &'static str,
+    prefix: &'static str,
+    body_contains: Option<&'static str>,
+    reply: Reply,
+    // Serve only the first matching request, then let later routes answer
+    once: bool,
+}
+
+pub struct StandIn {
+    url: String,
+    routes: Arc<Mutex<Vec<Route>>>,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl StandIn {
+    // Listen on a free local port; unmatched requests get a 404
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let routes: Arc<Mutex<Vec<Route>>> = Arc::default();
+        let requests: Arc<Mutex<Vec<Request>>> = Arc::default();
+
+        let (server_routes, server_requests) = (routes.clone(), requests.clone());
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle(stream, &server_routes, &server_requests);
+            }
+        });
+
+        Self { url, routes, requests }
+    }
+
+    // Base URL to use for both the web site and the API
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn route(&self, method: &'static str, prefix: &'static str, reply: Reply) -> &Self {
+        self.routes.lock().unwrap().push(Route { method, prefix, body_contains: None, reply, once: false });
+        self
+    }
+
+    // Answer the first matching request only, e.g. a failure before a retry succeeds
+    pub fn once(&self, method: &'static str, prefix: &'static str, reply: Reply) -> &Self {
+        self.routes.lock().unwrap().push(Route { method, prefix, body_contains: None, reply, once: true });
+        self
+    }
+
+    // Answer GraphQL queries that mention a field
+    pub fn graphql(&self, field: &'static str, reply: Reply) -> &Self {
+        let route = Route { method: "POST", prefix: "/graphql", body_contains: Some(field), reply, once: false };
+        self.routes.lock().unwrap().push(route);
+        self
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn handle(mut stream: TcpStream, routes: &Mutex<Vec<Route>>, requests: &Mutex<Vec<Request>>) {
+    let request = match read_request(&stream) {
+        Some(request) => request,
+        None => return,
+    };
+
+    let mut routes = routes.lock().unwrap();
+    let matched = routes.iter().position(|route| {
+        route.method == request.method
+            && request.target.starts_with(route.prefix)
+            && route.body_contains.is_none_or(|marker| request.body.contains(marker))
+    });
+    let reply = match matched {
+        Some(index) if routes[index].once => routes.remove(index).reply,
+        Some(index) => routes[index].reply.clone(),
+        None => Reply::new(404, "no route"),
+    };
+    drop(routes);
+    requests.lock().unwrap().push(request);
+
+    let mut head = format!("HTTP/1.1 {} Stand-in\r\nContent-Length: {}\r\nConnection: close\r\n", reply.status, reply.body.len());
+    for (name, value) in &reply.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    let _ = stream.write_all(format!("{}\r\n{}", head, reply.body).as_bytes());
+}
+
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let (method, target) = (parts.next()?.to_string(), parts.next()?.to_string());
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        match line.trim_end().split_once(':') {
+            Some((name, value)) => headers.push((name.trim().to_string(), value.trim().to_string())),
+            None => break,
+        }
+    }
+
+    let length = headers.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+
+    Some(Request { method, target, headers, body: String::from_utf8_lossy(&body).to_string() })
+}
@@ -0,0 +1,86 @@
+// Config file defaults and profiles.
+
+use gh_heat::config::{Config, Settings};
+use gh_heat::github::Source;
+use gh_heat::heatmap::WeekStart;
+use std::path::PathBuf;
+use std::process::Command;
+
+const CONFIG: &str = r##"
+[defaults]
+theme = "ice"
+days = 180
+week_start = "monday"
+
+[profile.work]
+host = "github.example.com"
+token_file = "/etc/gh-heat/work-token"
+source = "graphql"
+days = 90
+
+[profile.oss]
+theme = "viridis"
+
+[themes.ice]
+levels = ["#112233", "#445566"]
+"##;
+
+#[test]
+fn defaults_without_profile() {
+    let config = Config::parse(CONFIG).unwrap();
+    let settings = config.settings(None).unwrap();
+    assert_eq!(settings, Settings {
+        theme: Some("ice".to_string()),
+        days: Some(180),
+        week_start: Some(WeekStart::Monday),
+        ..Settings::default()
+    });
+}
+
+// A profile's settings replace the defaults they set and keep the others
+#[test]
+fn profile_over_defaults() {
+    let config = Config::parse(CONFIG).unwrap();
+    let settings = config.settings(Some("work")).unwrap();
+    assert_eq!(settings, Settings {
+        theme: Some("ice".to_string()),
+        days: Some(90),
+        week_start: Some(WeekStart::Monday),
+        source: Some(Source::Graphql),
+        host: Some("github.example.com".to_string()),
+        token_file: Some(PathBuf::from("/etc/gh-heat/work-token")),
+    });
+
+    assert_eq!(config.settings(Some("oss")).unwrap().theme.as_deref(), Some("viridis"));
+}
+
+#[test]
+fn unknown_profile() {
+    let config = Config::parse(CONFIG).unwrap();
+    let error = config.settings(Some("home")).unwrap_err().to_string();
+    assert!(error.contains("unknown profile 'home' (profiles: oss, work)"), "{}", error);
+}
+
+#[test]
+fn invalid_settings() {
+    assert!(Config::parse("[defaults]\nweek_start = \"tuesday\"\n").is_err());
+    assert!(Config::parse("[profile.work]\ncolour = \"red\"\n").is_err());
+}
+
+// The binary reads the config from XDG_CONFIG_HOME and exits with the config error code
+#[test]
+fn unknown_profile_exit_code() {
+    let dir = std::env::temp_dir().join(format!("gh-heat-config-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("gh-heat")).unwrap();
+    std::fs::write(dir.join("gh-heat/config.toml"), CONFIG).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_gh-heat"))
+        .args(["alice", "--profile", "home"])
+        .env("XDG_CONFIG_HOME", &dir)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(12));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown profile 'home'"));
+}
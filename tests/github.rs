@@ -0,0 +1,529 @@
+// Offline tests of GithubClient against a local stand-in serving recorded responses.
+
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use common::{Reply, StandIn};
+use gh_heat::activity::ActivityKind;
+use gh_heat::clock::Clock;
+use gh_heat::error::{exit, GhHeatError};
+use gh_heat::cache::Cache;
+use gh_heat::github::{Endpoints, GithubClient, Source};
+use gh_heat::heatmap::WeekStart;
+use gh_heat::watch::Watch;
+use gh_heat::zone;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn clock() -> Clock {
+    Clock::fixed(Utc.with_ymd_and_hms(2025, 3, 6, 12, 0, 0).unwrap(), zone::parse("UTC").unwrap())
+}
+
+fn client(server: &StandIn, token: Option<&str>) -> GithubClient {
+    let endpoints = Endpoints {
+        web: server.url().to_string(),
+        api: server.url().to_string(),
+        graphql: format!("{}/graphql", server.url()),
+    };
+    GithubClient::new(clock())
+        .unwrap()
+        .with_endpoints(endpoints)
+        .with_token(token.map(str::to_string))
+}
+
+// The GhHeatError behind a failed call
+fn error_of<T: std::fmt::Debug>(result: anyhow::Result<T>) -> GhHeatError {
+    result.unwrap_err().downcast::<GhHeatError>().expect("not a GhHeatError")
+}
+
+fn counts(days: &[(u32, u32)]) -> HashMap<NaiveDate, u32> {
+    days.iter().map(|(day, count)| (date(2025, 3, *day), *count)).collect()
+}
+
+#[test]
+fn graphql_calendar_with_token() {
+    let server = StandIn::start();
+    server.graphql("contributionCalendar", Reply::fixture("graphql_contributions.json"));
+
+    let contributions = client(&server, Some("secret"))
+        .get_user_contributions_between("alice", date(2025, 3, 1), date(2025, 3, 6))
+        .unwrap();
+    assert_eq!(contributions.counts, counts(&[(1, 3), (2, 4), (3, 0), (4, 9), (5, 2), (6, 6)]));
+    assert_eq!(contributions.source, Source::Graphql);
+    let private = contributions.private.unwrap();
+    assert_eq!((private.restricted, private.any_restricted, private.visible), (7, true, None));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].header("authorization"), Some("Bearer secret"));
+    let variables = requests[0].variables();
+    assert_eq!(variables["username"], "alice");
+    assert_eq!(variables["from"], "2025-03-01T00:00:00+00:00");
+    assert_eq!(variables["to"], "2025-03-06T23:59:59+00:00");
+}
+
+// GraphQL takes at most a year per query, so longer ranges are fetched in year-long windows
+#[test]
+fn graphql_calendar_longer_than_a_year() {
+    let server = StandIn::start();
+    server.graphql("contributionCalendar", Reply::fixture("graphql_contributions.json"));
+
+    let contributions = client(&server, Some("secret"))
+        .get_user_contributions_between("alice", date(2023, 3, 1), date(2025, 3, 6))
+        .unwrap();
+    // The fixture's days before March fall into the earlier windows and are kept there
+    let mut expected = counts(&[(1, 3), (2, 4), (3, 0), (4, 9), (5, 2), (6, 6)]);
+    expected.extend([(23, 2), (24, 0), (25, 5), (26, 1), (27, 0), (28, 7)].map(|(day, count)| (date(2025, 2, day), count)));
+    assert_eq!(contributions.counts, expected);
+    let private = contributions.private.unwrap();
+    assert_eq!((private.restricted, private.any_restricted), (21, true));
+
+    let windows: Vec<(String, String)> = server.requests()
+        .iter()
+        .map(|request| {
+            let variables = request.variables();
+            (variables["from"].as_str().unwrap().to_string(), variables["to"].as_str().unwrap().to_string())
+        })
+        .collect();
+    assert_eq!(windows, [
+        ("2023-03-01T00:00:00+00:00".to_string(), "2024-02-28T23:59:59+00:00".to_string()),
+        ("2024-02-29T00:00:00+00:00".to_string(), "2025-02-27T23:59:59+00:00".to_string()),
+        ("2025-02-28T00:00:00+00:00".to_string(), "2025-03-06T23:59:59+00:00".to_string()),
+    ]);
+}
+
+// By-repository counts the token can see raise days the calendar undercounts, without double counting
+#[test]
+fn private_contributions_folded() {
+    let server = StandIn::start();
+    server
+        .graphql("contributionCalendar", Reply::fixture("graphql_contributions.json"))
+        .graphql("pullRequestContributions", Reply::fixture("graphql_activity.json"));
+
+    let contributions = client(&server, Some("secret"))
+        .with_private(true)
+        .get_user_contributions_between("alice", date(2025, 3, 1), date(2025, 3, 6))
+        .unwrap();
+    // Three commits, a pull request, a review and an issue on the 5th, where the calendar has 2
+    assert_eq!(contributions.counts, counts(&[(1, 3), (2, 4), (3, 0), (4, 9), (5, 6), (6, 6)]));
+    let private = contributions.private.unwrap();
+    assert_eq!(private.visible, Some(1));
+    assert_eq!(private.folded, 4);
+}
+
+#[test]
+fn contribution_page_without_token() {
+    let server = StandIn::start();
+    server.route("GET", "/users/alice/contributions", Reply::fixture("contributions.html"));
+
+    let contributions = client(&server, None)
+        .get_user_contributions_between("alice", date(2025, 3, 1), date(2025, 3, 6))
+        .unwrap();
+    // Levels 0 to 4 become approximate counts
+    assert_eq!(contributions.counts, counts(&[(1, 0), (2, 1), (3, 4), (4, 8), (5, 12), (6, 1)]));
+    assert_eq!(contributions.source, Source::Scrape);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].target, "/users/alice/contributions?from=2025-03-01&to=2025-03-06");
+}
+
+#[test]
+fn graphql_server_error_falls_back_to_page() {
+    let server = StandIn::start();
+    server
+        .graphql("contributionCalendar", Reply::new(502, "Bad Gateway"))
+        .route("GET", "/users/alice/contributions", Reply::fixture("contributions.html"));
+
+    let contributions = client(&server, Some("secret"))
+        .get_user_contributions_between("alice", date(2025, 3, 1), date(2025, 3, 6))
+        .unwrap();
+    assert_eq!(contributions.counts[&date(2025, 3, 5)], 12);
+    assert_eq!(contributions.source, Source::Scrape);
+
+    let targets: Vec<String> = server.requests().into_iter().map(|request| request.target).collect();
+    assert_eq!(targets, ["/graphql", "/users/alice/contributions?from=2025-03-01&to=2025-03-06"]);
+}
+
+#[test]
+fn graphql_rate_limit_falls_back_to_page() {
+    let server = StandIn::start();
+    let limited = Reply::new(403, r#"{"message": "API rate limit exceeded"}"#)
+        .header("X-RateLimit-Limit", "5000")
+        .header("X-RateLimit-Remaining", "0")
+        .header("X-RateLimit-Reset", "1741262400");
+    server
+        .graphql("contributionCalendar", limited)
+        .route("GET", "/users/alice/contributions", Reply::fixture("contributions.html"));
+
+    let contributions = client(&server, Some("secret"))
+        .get_user_contributions_between("alice", date(2025, 3, 1), date(2025, 3, 6))
+        .unwrap();
+    assert_eq!(contributions.counts.len(), 6);
+}
+
+// A rejected token is reported instead of quietly showing public data
+#[test]
+fn graphql_unauthorized_does_not_fall_back() {
+    let server = StandIn::start();
+    server
+        .graphql("contributionCalendar", Reply::new(401, r#"{"message": "Bad credentials"}"#))
+        .route("GET", "/users/alice/contributions", Reply::fixture("contributions.html"));
+
+    let error = error_of(client(&server, Some("expired")).get_user_contributions_between("alice", date(2025, 3, 1), date(2025, 3, 6)));
+    assert!(matches!(&error, GhHeatError::Unauthorized(message) if message == "Bad credentials"), "{:?}", error);
+    assert!(server.requests().iter().all(|request| !request.target.starts_with("/users/")));
+}
+
+// --source graphql reports GraphQL failures instead of scraping
+#[test]
+fn graphql_source_is_strict() {
+    let server = StandIn::start();
+    server
+        .graphql("contributionCalendar", Reply::new(502, "Bad Gateway"))
+        .route("GET", "/users/alice/contributions", Reply::fixture("contributions.html"));
+
+    let result = client(&server, Some("secret"))
+        .with_source(Source::Graphql)
+        .get_user_contributions_between("alice", date(2025, 3, 1), date(2025, 3, 6));
+    let error = error_of(result);
+    assert!(matches!(error, GhHeatError::ServerError { status: 502, .. }), "{:?}", error);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn graphql_source_requires_token() {
+    let server = StandIn::start();
+
+    let error = error_of(client(&server, None).with_source(Source::Graphql).get_user_contributions("alice", 30));
+    assert!(matches!(error, GhHeatError::Unauthorized(_)), "{:?}", error);
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn scrape_source_skips_graphql() {
+    let server = StandIn::start();
+    server.route("GET", "/users/alice/contributions", Reply::fixture("contributions.html"));
+
+    let contributions = client(&server, Some("secret"))
+        .with_source(Source::Scrape)
+        .get_user_contributions_between("alice", date(2025, 3, 1), date(2025, 3, 6))
+        .unwrap();
+    assert_eq!(contributions.source, Source::Scrape);
+
+    let targets: Vec<String> = server.requests().into_iter().map(|request| request.target).collect();
+    assert_eq!(targets, ["/users/alice/contributions?from=2025-03-01&to=2025-03-06"]);
+}
+
+#[test]
+fn missing_contribution_page() {
+    let server = StandIn::start();
+    server.route("GET", "/users/nobody/contributions", Reply::new(404, "Not Found"));
+
+    let error = error_of(client(&server, None).get_user_contributions("nobody", 30));
+    assert!(matches!(&error, GhHeatError::UserNotFound(user) if user == "nobody"), "{:?}", error);
+    assert_eq!(error.exit_code(), exit::USER_NOT_FOUND);
+}
+
+// A sign-in or error page instead of a calendar is an error, not a year without contributions
+#[test]
+fn contribution_page_without_calendar() {
+    let server = StandIn::start();
+    let page = "<!DOCTYPE html><html><head><title>Sign in to GitHub</title></head><body></body></html>";
+    server.route("GET", "/users/alice/contributions", Reply::new(200, page));
+
+    let error = error_of(client(&server, None).get_user_contributions("alice", 30));
+    assert!(matches!(&error, GhHeatError::ParseFailure { snippet, .. } if snippet.contains("Sign in")), "{:?}", error);
+    assert_eq!(error.exit_code(), exit::PARSE);
+}
+
+#[test]
+fn contribution_page_server_error() {
+    let server = StandIn::start();
+    server.route("GET", "/users/alice/contributions", Reply::new(503, "Service Unavailable"));
+
+    let error = error_of(client(&server, None).get_user_contributions("alice", 30));
+    assert!(matches!(error, GhHeatError::ServerError { status: 503, .. }), "{:?}", error);
+    assert_eq!(error.exit_code(), exit::SERVER_ERROR);
+}
+
+#[test]
+fn events_weighted_by_push_size() {
+    let server = StandIn::start();
+    server
+        .route("GET", "/users/alice/events/public?per_page=100&page=1", Reply::fixture("events.json"))
+        .route("GET", "/users/alice/events/public?per_page=100&page=2", Reply::new(200, "[]"));
+
+    let timestamps = client(&server, None).get_event_timestamps("alice").unwrap();
+    // Three commits in the push, one issue and one star
+    assert_eq!(timestamps.len(), 5);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn events_rate_limited() {
+    let server = StandIn::start();
+    let limited = Reply::new(403, r#"{"message": "API rate limit exceeded for 127.0.0.1."}"#)
+        .header("X-RateLimit-Limit", "60")
+        .header("X-RateLimit-Remaining", "0")
+        .header("X-RateLimit-Reset", "1741262400");
+    server.route("GET", "/users/alice/events/public", limited);
+
+    let error = error_of(client(&server, None).get_event_timestamps("alice"));
+    assert!(matches!(error, GhHeatError::RateLimit { secondary: false, retry_at: Some(_) }), "{:?}", error);
+    // X-RateLimit-Reset, in the clock's zone
+    assert_eq!(error.to_string(), "Rate limit exceeded. Try again after 2025-03-06 12:00:00 +00:00.");
+}
+
+#[test]
+fn secondary_rate_limit_retried() {
+    let server = StandIn::start();
+    let limited = Reply::new(403, r#"{"message": "You have exceeded a secondary rate limit."}"#)
+        .header("Retry-After", "0");
+    server
+        .once("GET", "/users/alice/events/public", limited)
+        .route("GET", "/users/alice/events/public", Reply::new(200, "[]"));
+
+    let timestamps = client(&server, None).with_retries(2).get_event_timestamps("alice").unwrap();
+    assert!(timestamps.is_empty());
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn secondary_rate_limit_without_retries() {
+    let server = StandIn::start();
+    let limited = Reply::new(403, r#"{"message": "You have exceeded a secondary rate limit."}"#)
+        .header("Retry-After", "90");
+    server.route("GET", "/users/alice/events/public", limited);
+
+    let error = error_of(client(&server, None).get_event_timestamps("alice"));
+    assert!(matches!(error, GhHeatError::RateLimit { secondary: true, .. }), "{:?}", error);
+    assert_eq!(error.to_string(), "Secondary rate limit exceeded. Try again after 2025-03-06 12:01:30 +00:00.");
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn server_error_retried() {
+    let server = StandIn::start();
+    server
+        .once("GET", "/users/alice/contributions", Reply::new(502, "Bad Gateway").header("Retry-After", "0"))
+        .route("GET", "/users/alice/contributions", Reply::fixture("contributions.html"));
+
+    let contributions = client(&server, None)
+        .with_retries(1)
+        .get_user_contributions_between("alice", date(2025, 3, 1), date(2025, 3, 6))
+        .unwrap();
+    assert_eq!(contributions.counts.len(), 6);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn forbidden_without_scope() {
+    let server = StandIn::start();
+    let forbidden = Reply::new(403, r#"{"message": "Resource not accessible by integration"}"#)
+        .header("X-RateLimit-Remaining", "4999")
+        .header("X-Accepted-OAuth-Scopes", "repo")
+        .header("X-OAuth-Scopes", "read:user");
+    server.route("GET", "/users/alice/events/public", forbidden);
+
+    let error = error_of(client(&server, Some("secret")).with_retries(3).get_event_timestamps("alice"));
+    assert!(matches!(&error, GhHeatError::InsufficientScope(message) if message.contains("repo")), "{:?}", error);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn events_server_error() {
+    let server = StandIn::start();
+    server.route("GET", "/users/alice/events/public", Reply::new(500, "Internal Server Error"));
+
+    let error = error_of(client(&server, None).get_event_timestamps("alice"));
+    assert!(matches!(error, GhHeatError::ServerError { status: 500, .. }), "{:?}", error);
+}
+
+#[test]
+fn events_malformed() {
+    let server = StandIn::start();
+    let body = r#"[{"type": "PushEvent", "created_at": "2025-03-05T10:00:00Z", "payload": {"size": 2}},, {}]"#;
+    server.route("GET", "/users/alice/events/public", Reply::new(200, body));
+
+    let error = error_of(client(&server, None).get_event_timestamps("alice"));
+    assert!(matches!(&error, GhHeatError::ParseFailure { snippet, .. } if snippet.contains("},, {}]")), "{:?}", error);
+}
+
+#[test]
+fn bad_credentials() {
+    let server = StandIn::start();
+    server.route("GET", "/users/alice/events/public", Reply::new(401, r#"{"message": "Bad credentials"}"#));
+
+    let error = error_of(client(&server, Some("expired")).get_event_timestamps("alice"));
+    assert!(matches!(&error, GhHeatError::Unauthorized(message) if message == "Bad credentials"), "{:?}", error);
+    assert_eq!(error.exit_code(), exit::UNAUTHORIZED);
+}
+
+#[test]
+fn day_activity_from_graphql() {
+    let server = StandIn::start();
+    server
+        .graphql("pullRequestContributions", Reply::fixture("graphql_activity.json"))
+        .graphql("login", Reply::fixture("graphql_repositories.json"))
+        .graphql("history(", Reply::fixture("graphql_history.json"));
+
+    let activities = client(&server, Some("secret")).get_day_activity("alice", date(2025, 3, 5)).unwrap();
+    let commits: Vec<(&str, Option<&str>)> = activities.iter()
+        .filter(|activity| activity.kind == ActivityKind::Commits)
+        .map(|activity| (activity.repository.as_str(), activity.title.as_deref()))
+        .collect();
+    // Commits on the default branch are listed one by one; others only as a count
+    assert_eq!(commits, [
+        ("alice/heat", Some("Fix week alignment")),
+        ("alice/heat", Some("Add golden tests")),
+        ("alice/notes", None),
+    ]);
+
+    let titles: Vec<&str> = activities.iter()
+        .filter(|activity| activity.kind != ActivityKind::Commits)
+        .filter_map(|activity| activity.title.as_deref())
+        .collect();
+    assert_eq!(titles, ["Add day view", "Speed up parser", "Crash on empty calendar"]);
+
+    // The history query is scoped to the day and the user's id
+    let history = server.requests().into_iter().find(|request| request.body.contains("history(")).unwrap();
+    let variables = history.variables();
+    assert_eq!(variables["author"], "U_alice");
+    assert_eq!(variables["since"], "2025-03-05T00:00:00+00:00");
+    assert_eq!(variables["until"], "2025-03-05T23:59:59+00:00");
+}
+
+#[test]
+fn day_activity_requires_token() {
+    let server = StandIn::start();
+
+    let error = error_of(client(&server, None).get_day_activity("alice", date(2025, 3, 5)));
+    assert!(matches!(&error, GhHeatError::Unauthorized(message) if message.contains("GITHUB_TOKEN")), "{:?}", error);
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn graphql_errors_are_reported() {
+    let server = StandIn::start();
+    let body = r#"{"data": null, "errors": [{"message": "Could not resolve to a User with the login of 'ghost'."}]}"#;
+    server.graphql("pullRequestContributions", Reply::new(200, body));
+
+    let error = error_of(client(&server, Some("secret")).get_day_activity("ghost", date(2025, 3, 5)));
+    assert!(matches!(&error, GhHeatError::Api(message) if message.contains("Could not resolve")), "{:?}", error);
+}
+
+#[test]
+fn graphql_unknown_user() {
+    let server = StandIn::start();
+    server.graphql("pullRequestContributions", Reply::new(200, r#"{"data": {"user": null}}"#));
+
+    let error = error_of(client(&server, Some("secret")).get_activity("ghost", date(2025, 3, 1), date(2025, 3, 5)));
+    assert!(matches!(&error, GhHeatError::UserNotFound(user) if user == "ghost"), "{:?}", error);
+}
+
+#[test]
+fn graphql_not_found_error() {
+    let server = StandIn::start();
+    let body = r#"{"data": {"user": null}, "errors": [{"type": "NOT_FOUND", "message": "Could not resolve to a User with the login of 'ghost'."}]}"#;
+    server.graphql("pullRequestContributions", Reply::new(200, body));
+
+    let error = error_of(client(&server, Some("secret")).get_day_activity("ghost", date(2025, 3, 5)));
+    assert!(matches!(&error, GhHeatError::UserNotFound(user) if user == "ghost"), "{:?}", error);
+}
+
+#[test]
+fn commit_history_rate_limited() {
+    let server = StandIn::start();
+    let limited = Reply::new(403, r#"{"message": "API rate limit exceeded"}"#)
+        .header("X-RateLimit-Remaining", "0")
+        .header("X-RateLimit-Reset", "1741262400");
+    server.graphql("login", limited);
+
+    let error = error_of(client(&server, Some("secret")).get_commit_timestamps("alice", 30));
+    assert!(matches!(error, GhHeatError::RateLimit { secondary: false, .. }), "{:?}", error);
+}
+
+#[test]
+fn graphql_rate_limited_in_body() {
+    let server = StandIn::start();
+    let body = r#"{"data": null, "errors": [{"type": "RATE_LIMITED", "message": "API rate limit exceeded"}]}"#;
+    let limited = Reply::new(200, body)
+        .header("X-RateLimit-Remaining", "0")
+        .header("X-RateLimit-Reset", "1741262400");
+    server.graphql("pullRequestContributions", limited);
+
+    let error = error_of(client(&server, Some("secret")).get_day_activity("alice", date(2025, 3, 5)));
+    assert!(matches!(error, GhHeatError::RateLimit { secondary: false, retry_at: Some(_) }), "{:?}", error);
+}
+
+// GitHub Enterprise Server serves the REST API under /api/v3 and GraphQL under /api/graphql
+#[test]
+fn enterprise_endpoints() {
+    let endpoints = Endpoints::for_host("github.example.com");
+    assert_eq!(endpoints.web, "https://github.example.com");
+    assert_eq!(endpoints.api, "https://github.example.com/api/v3");
+    assert_eq!(endpoints.graphql, "https://github.example.com/api/graphql");
+    assert_eq!(Endpoints::for_host("github.com"), Endpoints::default());
+    assert_eq!(endpoints.host(), "github.example.com");
+    assert_eq!(Endpoints::default().host(), "github.com");
+}
+
+// Without a token given, the token variables are read for the host the client ends up sending to
+#[test]
+fn token_from_environment_for_endpoint_host() {
+    std::env::set_var("GH_ENTERPRISE_TOKEN", "enterprise-secret");
+    let server = StandIn::start();
+    server.graphql("contributionCalendar", Reply::fixture("graphql_contributions.json"));
+
+    let endpoints = Endpoints {
+        web: server.url().to_string(),
+        api: server.url().to_string(),
+        graphql: format!("{}/graphql", server.url()),
+    };
+    GithubClient::new(clock())
+        .unwrap()
+        .with_endpoints(endpoints)
+        .get_user_contributions_between("alice", date(2025, 3, 1), date(2025, 3, 6))
+        .unwrap();
+    assert_eq!(server.requests()[0].header("authorization"), Some("Bearer enterprise-secret"));
+}
+
+// A watch refresh fetches the current week, saves it to the cache and reports the days that went up
+#[test]
+fn watch_refresh_merges_current_week() {
+    let dir = std::env::temp_dir().join(format!("gh-heat-watch-{}", std::process::id()));
+    std::env::set_var("XDG_CACHE_HOME", &dir);
+    let server = StandIn::start();
+    server.graphql("contributionCalendar", Reply::fixture("graphql_contributions.json"));
+
+    let client = client(&server, Some("secret")).with_quiet(true);
+    let watch = Watch {
+        client: &client,
+        username: "alice",
+        days: 30,
+        week_start: WeekStart::Sunday,
+        interval: Duration::from_secs(60),
+        clock: clock(),
+        width: None,
+    };
+    let mut cache = Cache::load("alice").unwrap();
+    let earlier = [(date(2025, 3, 4), 5), (date(2025, 3, 5), 2), (date(2025, 3, 6), 1)];
+    cache.merge(&earlier.into_iter().collect(), clock().now_utc());
+
+    let (increased, fallback) = watch.refresh(&mut cache).unwrap();
+    assert_eq!(increased, [date(2025, 3, 2), date(2025, 3, 4), date(2025, 3, 6)]);
+    assert_eq!(fallback, None);
+    let variables = server.requests()[0].variables();
+    assert_eq!(variables["from"], "2025-03-02T00:00:00+00:00");
+    assert_eq!(variables["to"], "2025-03-06T23:59:59+00:00");
+
+    let saved = Cache::load("alice").unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(saved.contributions[&date(2025, 3, 4)], 9);
+    assert_eq!(saved.updated, Some(clock().now_utc()));
+}
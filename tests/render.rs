@@ -0,0 +1,336 @@
+// Golden-output tests for the heatmap renderer, run against fixed fixture data and a fixed clock.
+// Set UPDATE_GOLDEN=1 to rewrite the files in tests/golden after an intended change.
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use gh_heat::clock::Clock;
+use gh_heat::config::Config;
+use gh_heat::heatmap::{Heatmap, Orientation, RenderMode, RenderOptions, Span, WeekStart};
+use gh_heat::locale::Locale;
+use gh_heat::overlay::Overlays;
+use gh_heat::term::ColorSupport;
+use gh_heat::theme::{Rgb, Theme};
+use gh_heat::zone;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+// The day after the fixture data ends, at noon UTC
+fn clock() -> Clock {
+    Clock::fixed(Utc.with_ymd_and_hms(2025, 3, 6, 12, 0, 0).unwrap(), zone::parse("UTC").unwrap())
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+// Counts from tests/fixtures/contributions.csv
+fn fixture() -> HashMap<NaiveDate, u32> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/contributions.csv");
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.starts_with("date"))
+        .map(|line| {
+            let (date, count) = line.split_once(',').unwrap();
+            (NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(), count.parse().unwrap())
+        })
+        .collect()
+}
+
+// The fixture between two dates
+fn fixture_between(start_date: NaiveDate, end_date: NaiveDate, week_start: WeekStart) -> Heatmap {
+    Heatmap::with_date_range(fixture(), week_start, start_date, end_date).between(start_date, end_date)
+}
+
+fn theme() -> Theme {
+    Theme::load("github", &Config::default()).unwrap()
+}
+
+fn english() -> &'static Locale {
+    Locale::from_name("en").unwrap()
+}
+
+const NO_OVERLAYS: &Overlays = &Overlays {
+    today: None,
+    dim_weekends: false,
+    holidays: BTreeMap::new(),
+    markers: BTreeMap::new(),
+};
+
+fn options<'a>(theme: &'a Theme, mode: RenderMode, colors: ColorSupport) -> RenderOptions<'a> {
+    RenderOptions {
+        theme,
+        colors,
+        mode,
+        max_width: None,
+        week_numbers: false,
+        locale: english(),
+        orientation: Orientation::Horizontal,
+        cursor: None,
+        highlights: &[],
+        overlays: NO_OVERLAYS,
+    }
+}
+
+// Compare output with tests/golden/<name>.txt, or rewrite the file when UPDATE_GOLDEN is set
+fn assert_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("{}.txt", name));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing {}; run with UPDATE_GOLDEN=1 to create it", path.display()));
+    assert!(
+        expected == actual,
+        "output differs from {}\n--- expected\n{}\n--- actual\n{}",
+        path.display(),
+        expected,
+        actual
+    );
+}
+
+fn render_fixture(name: &str, mode: RenderMode, colors: ColorSupport) {
+    let theme = theme().for_colors(colors);
+    let heatmap = Heatmap::new(fixture(), WeekStart::Sunday, clock().today());
+    assert_golden(name, &heatmap.render_to_string(&options(&theme, mode, colors)));
+}
+
+#[test]
+fn blocks_truecolor() {
+    render_fixture("blocks_truecolor", RenderMode::Blocks, ColorSupport::TrueColor);
+}
+
+#[test]
+fn blocks_ansi256() {
+    render_fixture("blocks_ansi256", RenderMode::Blocks, ColorSupport::Ansi256);
+}
+
+#[test]
+fn blocks_ansi16() {
+    render_fixture("blocks_ansi16", RenderMode::Blocks, ColorSupport::Ansi16);
+}
+
+// On 16-color terminals every built-in theme keeps distinct levels in the order of its lightness
+#[test]
+fn ansi16_ramps_stay_ordered() {
+    let lightness = |rgb: Rgb| 299 * rgb.0 as u32 + 587 * rgb.1 as u32 + 114 * rgb.2 as u32;
+    let config = Config::default();
+    for name in Theme::names(&config) {
+        let theme = Theme::load(&name, &config).unwrap();
+        let ramp = theme.for_colors(ColorSupport::Ansi16).levels;
+        let darkening = lightness(theme.levels[4]) < lightness(theme.levels[0]);
+        for pair in ramp.windows(2) {
+            assert_ne!(pair[0], pair[1], "{}", name);
+            assert_eq!(lightness(pair[1]) < lightness(pair[0]), darkening, "{}", name);
+        }
+        assert!(!ramp.contains(&Rgb(0, 0, 0)), "{}", name);
+    }
+}
+
+#[test]
+fn symbols_plain() {
+    render_fixture("symbols_plain", RenderMode::Symbols, ColorSupport::None);
+}
+
+#[test]
+fn symbols_colored() {
+    render_fixture("symbols_colored", RenderMode::Symbols, ColorSupport::TrueColor);
+}
+
+#[test]
+fn numbers() {
+    render_fixture("numbers", RenderMode::Numbers, ColorSupport::None);
+}
+
+#[test]
+fn half_blocks() {
+    render_fixture("half_truecolor", RenderMode::Half, ColorSupport::TrueColor);
+    render_fixture("half_plain", RenderMode::Half, ColorSupport::None);
+}
+
+#[test]
+fn quadrants() {
+    render_fixture("quadrant_truecolor", RenderMode::Quadrant, ColorSupport::TrueColor);
+    render_fixture("quadrant_plain", RenderMode::Quadrant, ColorSupport::None);
+}
+
+#[test]
+fn braille() {
+    render_fixture("braille_truecolor", RenderMode::Braille, ColorSupport::TrueColor);
+    render_fixture("braille_plain", RenderMode::Braille, ColorSupport::None);
+}
+
+#[test]
+fn vertical() {
+    let theme = theme();
+    let heatmap = Heatmap::new(fixture(), WeekStart::Sunday, clock().today());
+    let options = RenderOptions {
+        orientation: Orientation::Vertical,
+        ..options(&theme, RenderMode::Symbols, ColorSupport::None)
+    };
+    assert_golden("vertical_symbols", &heatmap.render_to_string(&options));
+}
+
+#[test]
+fn monday_start_with_week_numbers() {
+    let theme = theme();
+    let heatmap = Heatmap::new(fixture(), WeekStart::Monday, clock().today());
+    let options = RenderOptions {
+        week_numbers: true,
+        ..options(&theme, RenderMode::Symbols, ColorSupport::None)
+    };
+    assert_golden("monday_week_numbers", &heatmap.render_to_string(&options));
+}
+
+#[test]
+fn saturday_start() {
+    let theme = theme();
+    let heatmap = Heatmap::new(fixture(), WeekStart::Saturday, clock().today());
+    assert_golden("saturday_start", &heatmap.render_to_string(&options(&theme, RenderMode::Symbols, ColorSupport::None)));
+}
+
+#[test]
+fn wrapped_into_bands() {
+    let theme = theme();
+    let heatmap = Heatmap::new(fixture(), WeekStart::Sunday, clock().today());
+    let options = RenderOptions {
+        max_width: Some(24),
+        ..options(&theme, RenderMode::Symbols, ColorSupport::None)
+    };
+    assert_golden("wrapped_bands", &heatmap.render_to_string(&options));
+}
+
+// December 2024 starts on a Sunday, so its label sits over the first column
+#[test]
+fn month_starting_on_week_start() {
+    let theme = theme();
+    let heatmap = fixture_between(date(2024, 12, 1), date(2025, 1, 4), WeekStart::Sunday);
+    assert_golden("month_on_week_start", &heatmap.render_to_string(&options(&theme, RenderMode::Symbols, ColorSupport::None)));
+}
+
+// The first column starts in January and the second in February, too close for both labels
+#[test]
+fn month_labels_colliding() {
+    let theme = theme();
+    let heatmap = fixture_between(date(2025, 1, 29), date(2025, 3, 5), WeekStart::Sunday);
+    assert_golden("month_labels_colliding", &heatmap.render_to_string(&options(&theme, RenderMode::Symbols, ColorSupport::None)));
+}
+
+// March 2025 starts on the last row of a week that is labeled February
+#[test]
+fn month_starting_on_last_row() {
+    let theme = theme();
+    let heatmap = fixture_between(date(2025, 2, 16), date(2025, 3, 5), WeekStart::Sunday);
+    assert_golden("month_on_last_row", &heatmap.render_to_string(&options(&theme, RenderMode::Numbers, ColorSupport::None)));
+}
+
+// A range shorter than a week fills the rest of the column
+#[test]
+fn partial_week() {
+    let theme = theme();
+    let heatmap = fixture_between(date(2024, 12, 31), date(2025, 1, 2), WeekStart::Sunday);
+    assert_golden("partial_week", &heatmap.render_to_string(&options(&theme, RenderMode::Numbers, ColorSupport::None)));
+}
+
+// Without contributions the grid is just today, from the clock
+#[test]
+fn empty_uses_clock() {
+    let theme = theme();
+    let heatmap = Heatmap::new(HashMap::new(), WeekStart::Sunday, clock().today());
+    assert_golden("empty", &heatmap.render_to_string(&options(&theme, RenderMode::Symbols, ColorSupport::None)));
+}
+
+// Double-width month names stay aligned with their columns
+#[test]
+fn wide_month_labels() {
+    let theme = theme();
+    let heatmap = Heatmap::new(fixture(), WeekStart::Sunday, clock().today());
+    let options = RenderOptions {
+        locale: Locale::from_name("ja").unwrap(),
+        ..options(&theme, RenderMode::Symbols, ColorSupport::None)
+    };
+    assert_golden("japanese_labels", &heatmap.render_to_string(&options));
+}
+
+#[test]
+fn overlays() {
+    let theme = theme();
+    let overlays = Overlays {
+        today: Some(date(2025, 3, 5)),
+        dim_weekends: true,
+        holidays: [(date(2024, 12, 25), "Christmas Day".to_string())].into_iter().collect(),
+        markers: (24..=31).map(|day| (date(2025, 1, day), "Vacation".to_string())).collect(),
+    };
+    let heatmap = Heatmap::new(fixture(), WeekStart::Sunday, clock().today());
+    let options = RenderOptions {
+        overlays: &overlays,
+        ..options(&theme, RenderMode::Blocks, ColorSupport::TrueColor)
+    };
+    assert_golden("overlays", &heatmap.render_to_string(&options));
+}
+
+#[test]
+fn overlays_compact() {
+    let theme = theme();
+    let overlays = Overlays {
+        holidays: [(date(2024, 12, 25), "Christmas Day".to_string())].into_iter().collect(),
+        ..Default::default()
+    };
+    let heatmap = Heatmap::new(fixture(), WeekStart::Sunday, clock().today());
+    let options = RenderOptions {
+        overlays: &overlays,
+        ..options(&theme, RenderMode::Half, ColorSupport::TrueColor)
+    };
+    let rendered = heatmap.render_to_string(&options);
+    assert!(!rendered.contains("Christmas Day"));
+    assert_golden("overlays_compact", &rendered);
+}
+
+fn span(start: NaiveDate, end: NaiveDate) -> Option<Span> {
+    Some(Span { start, end, days: (end - start).num_days() as u32 + 1 })
+}
+
+// Counts for a range of days, zero on every day not listed, as the API reports them
+fn counts(start_date: NaiveDate, end_date: NaiveDate, active: &[(NaiveDate, u32)]) -> Heatmap {
+    let mut contributions: HashMap<NaiveDate, u32> =
+        start_date.iter_days().take_while(|day| *day <= end_date).map(|day| (day, 0)).collect();
+    contributions.extend(active.iter().copied());
+    Heatmap::with_date_range(contributions, WeekStart::Sunday, start_date, end_date)
+}
+
+// The days before the first contribution are not a gap
+#[test]
+fn streaks_and_gap() {
+    let active: Vec<(NaiveDate, u32)> = (1..=3).map(|day| (date(2025, 3, day), 1))
+        .chain((8..=9).map(|day| (date(2025, 3, day), 2)))
+        .chain([(date(2025, 3, 14), 1)])
+        .collect();
+    let heatmap = counts(date(2025, 1, 1), date(2025, 3, 15), &active);
+    let streaks = heatmap.streaks(false);
+    assert_eq!(streaks.longest, span(date(2025, 3, 1), date(2025, 3, 3)));
+    assert_eq!(streaks.longest_gap, span(date(2025, 3, 4), date(2025, 3, 7)));
+    // The last day without contributions yet doesn't break the current streak
+    assert_eq!(streaks.current, span(date(2025, 3, 14), date(2025, 3, 14)));
+
+    let heatmap = counts(date(2025, 1, 1), date(2025, 3, 16), &active);
+    assert_eq!(heatmap.streaks(false).current, None);
+    assert_eq!(counts(date(2025, 1, 1), date(2025, 1, 31), &[]).streaks(false).longest_gap, None);
+}
+
+// Weekends neither extend nor break a weekday streak, and weekend work doesn't start one
+#[test]
+fn weekday_streaks() {
+    // March 1 and 2, 2025 are a weekend
+    let active: Vec<(NaiveDate, u32)> = [1, 3, 4, 5, 6, 7, 10, 11].iter().map(|day| (date(2025, 3, *day), 1)).collect();
+    let heatmap = counts(date(2025, 2, 1), date(2025, 3, 11), &active);
+
+    let streaks = heatmap.streaks(true);
+    assert_eq!(streaks.longest, Some(Span { start: date(2025, 3, 3), end: date(2025, 3, 11), days: 7 }));
+    assert_eq!(streaks.current, streaks.longest);
+    assert_eq!(streaks.longest_gap, None);
+
+    let streaks = heatmap.streaks(false);
+    assert_eq!(streaks.longest, span(date(2025, 3, 3), date(2025, 3, 7)));
+    assert_eq!(streaks.longest_gap, span(date(2025, 3, 8), date(2025, 3, 9)));
+}
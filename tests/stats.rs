@@ -0,0 +1,47 @@
+use chrono::NaiveDate;
+use gh_heat::heatmap::{Heatmap, WeekStart};
+use gh_heat::stats::{Report, YearTotal};
+use std::collections::HashMap;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+// One contribution a day, and ten on the days from `busy`, over a range of days
+fn heatmap(start_date: NaiveDate, end_date: NaiveDate, busy: NaiveDate) -> Heatmap {
+    let contributions: HashMap<NaiveDate, u32> = start_date.iter_days()
+        .take_while(|day| *day <= end_date)
+        .map(|day| (day, if day >= busy { 10 } else { 1 }))
+        .collect();
+    Heatmap::with_date_range(contributions, WeekStart::Sunday, start_date, end_date)
+}
+
+fn yearly(heatmap: &Heatmap) -> Vec<(i32, u32, Option<i64>)> {
+    Report::new(heatmap).yearly_totals.iter()
+        .map(|YearTotal { year, total, change_percent }| (*year, *total, change_percent.map(|change| change.round() as i64)))
+        .collect()
+}
+
+// A year that only partly falls in the range isn't compared with a whole year
+#[test]
+fn yearly_change_needs_the_same_days_the_year_before() {
+    let heatmap = heatmap(date(2024, 3, 6), date(2025, 3, 5), date(2025, 1, 1));
+    assert_eq!(yearly(&heatmap), vec![(2024, 301, None), (2025, 640, None)]);
+}
+
+// A partial last year is compared with the same days of the year before
+#[test]
+fn yearly_change_over_the_same_days() {
+    let heatmap = heatmap(date(2023, 1, 1), date(2025, 3, 5), date(2025, 1, 1));
+    assert_eq!(
+        yearly(&heatmap),
+        vec![(2023, 365, None), (2024, 366, Some(0)), (2025, 640, Some(885))]
+    );
+}
+
+// February 29 is compared with February 28 of the year before
+#[test]
+fn yearly_change_on_leap_day() {
+    let heatmap = heatmap(date(2023, 1, 1), date(2024, 2, 29), date(2024, 1, 1));
+    assert_eq!(yearly(&heatmap), vec![(2023, 365, None), (2024, 600, Some(917))]);
+}
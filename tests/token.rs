@@ -0,0 +1,66 @@
+// Token discovery from gh's hosts.yml and --token-file.
+
+use gh_heat::token::{self, TokenSource};
+use std::path::Path;
+
+fn hosts() -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/gh/hosts.yml");
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn gh_hosts_active_account() {
+    assert_eq!(token::parse_gh_hosts(&hosts(), "github.com").as_deref(), Some("gho_alice_work"));
+}
+
+// Without a token directly under the host, the first listed user's token is used
+#[test]
+fn gh_hosts_user_token() {
+    assert_eq!(token::parse_gh_hosts(&hosts(), "ghe.example.com").as_deref(), Some("ghe_alice"));
+}
+
+#[test]
+fn gh_hosts_unknown_host() {
+    assert_eq!(token::parse_gh_hosts(&hosts(), "gitlab.com"), None);
+    assert_eq!(token::parse_gh_hosts("", "github.com"), None);
+}
+
+#[test]
+fn token_file() {
+    let path = std::env::temp_dir().join(format!("gh-heat-token-{}", std::process::id()));
+    std::fs::write(&path, "\n  ghp_secret  \n").unwrap();
+    let token = token::discover(token::GITHUB_HOST, Some(&path)).unwrap().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(token.value, "ghp_secret");
+    assert_eq!(token.source, token::TokenSource::File(path));
+    // Debug output never shows the token
+    assert!(!format!("{:?}", token).contains("ghp_secret"));
+}
+
+#[test]
+fn empty_token_file() {
+    let path = std::env::temp_dir().join(format!("gh-heat-empty-token-{}", std::process::id()));
+    std::fs::write(&path, "\n").unwrap();
+    let result = token::discover(token::GITHUB_HOST, Some(&path));
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(result.is_err());
+}
+
+// A github.com token is never sent to an Enterprise Server host, which has its own variables
+#[test]
+fn enterprise_token_variables() {
+    let vars = |name: &str| match name {
+        "GH_TOKEN" => Some("github_com".to_string()),
+        "GITHUB_ENTERPRISE_TOKEN" => Some("ghes".to_string()),
+        _ => None,
+    };
+
+    let token = token::from_vars("github.com", vars).unwrap();
+    assert_eq!((token.value.as_str(), token.source), ("github_com", TokenSource::Env("GH_TOKEN")));
+    let token = token::from_vars("ghe.example.com", vars).unwrap();
+    assert_eq!((token.value.as_str(), token.source), ("ghes", TokenSource::Env("GITHUB_ENTERPRISE_TOKEN")));
+    assert!(token::from_vars("octo.ghe.com", vars).is_some_and(|token| token.value == "github_com"));
+    assert!(token::from_vars("ghe.example.com", |name| vars(name).filter(|_| name == "GH_TOKEN")).is_none());
+}